@@ -0,0 +1,42 @@
+use std::{
+    env,
+    fs,
+    path::Path,
+};
+
+// With the embed-inputs feature, generate a registry that include_str!s every
+// puzzle input under src/inputs so the binary does not need them at runtime
+fn main() {
+    println!("cargo:rerun-if-changed=build.rs");
+    println!("cargo:rerun-if-changed=src/inputs");
+
+    if env::var_os("CARGO_FEATURE_EMBED_INPUTS").is_none() {
+        return;
+    }
+
+    let manifest_dir = env::var("CARGO_MANIFEST_DIR").unwrap();
+    let inputs_dir = Path::new(&manifest_dir).join("src").join("inputs");
+
+    let mut days: Vec<(u32, String)> = Vec::new();
+    for entry in fs::read_dir(&inputs_dir).expect("no inputs directory") {
+        let name = entry.unwrap().file_name().into_string().unwrap();
+        if let Some(number) = name.strip_prefix("day").and_then(|x| x.parse::<u32>().ok()) {
+            days.push((number, name));
+        }
+    }
+    days.sort();
+
+    let mut registry = String::new();
+    registry.push_str("pub fn embedded(day: &str) -> Option<&'static str> {\n");
+    registry.push_str("    return match day {\n");
+    for (_, name) in &days {
+        let path = inputs_dir.join(name);
+        registry.push_str(&format!("        {:?} => Some(include_str!({:?})),\n", name, path.to_str().unwrap()));
+    }
+    registry.push_str("        _ => None,\n");
+    registry.push_str("    };\n");
+    registry.push_str("}\n");
+
+    let out_dir = env::var("OUT_DIR").unwrap();
+    fs::write(Path::new(&out_dir).join("inputs.rs"), registry).unwrap();
+}
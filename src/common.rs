@@ -1,3 +1,21 @@
 pub mod io;
+#[cfg(feature = "embed-inputs")]
+pub mod inputs;
 pub mod convertor;
-pub mod search;
\ No newline at end of file
+pub mod search;
+pub mod hex;
+pub mod math;
+pub mod grid;
+pub mod automaton;
+pub mod geom;
+pub mod graph;
+pub mod cycle;
+pub mod ksum;
+pub mod subarray;
+// No day groups anything yet, the union-find is only built for its tests
+#[cfg(test)]
+pub mod dsu;
+pub mod interval;
+pub mod parse;
+pub mod bits;
+pub mod memo;
\ No newline at end of file
@@ -0,0 +1,268 @@
+use std::collections::HashMap;
+use std::collections::HashSet;
+use std::hash::Hash;
+
+use crate::common::grid::Grid;
+
+// States of a cell's neighbors, handed to the transition rule
+pub struct Neighbors<'a, T> {
+    grid: &'a Grid<T>,
+    positions: std::slice::Iter<'a, (usize, usize)>,
+}
+
+impl<'a, T> Iterator for Neighbors<'a, T> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<&'a T> {
+        self.positions.next().map(|position| &self.grid[*position])
+    }
+}
+
+// Cellular automaton on a fixed size grid. The neighborhood of every cell is
+// worked out once up front, each step applies the rule to every cell at once
+// by writing into a second buffer and swapping
+pub struct GridAutomaton<T, R> {
+    grid: Grid<T>,
+    next: Grid<T>,
+    neighbors: Grid<Vec<(usize, usize)>>,
+    rule: R,
+    generation: usize,
+}
+
+impl<T, R> GridAutomaton<T, R>
+where T: Clone + PartialEq, R: Fn(&T, Neighbors<T>) -> T {
+    pub fn new(grid: Grid<T>, neighbors: Grid<Vec<(usize, usize)>>, rule: R) -> GridAutomaton<T, R> {
+        let next = grid.clone();
+        GridAutomaton {grid, next, neighbors, rule, generation: 0}
+    }
+
+    // Neighborhood given as a function of the starting grid and a cell position
+    pub fn with_neighborhood<N>(grid: Grid<T>, neighborhood: N, rule: R) -> GridAutomaton<T, R>
+    where N: Fn(&Grid<T>, usize, usize) -> Vec<(usize, usize)> {
+        let neighbors = Grid::from_vec(
+            grid.width(),
+            grid.height(),
+            grid.positions().map(|((x, y), _)| neighborhood(&grid, x, y)).collect());
+        GridAutomaton::new(grid, neighbors, rule)
+    }
+
+    pub fn grid(&self) -> &Grid<T> {
+        &self.grid
+    }
+
+    pub fn generation(&self) -> usize {
+        self.generation
+    }
+
+    // Advance one generation, returns how many cells changed
+    pub fn step(&mut self) -> usize {
+        let mut changed = 0;
+
+        for ((x, y), cell) in self.grid.positions() {
+            let neighbors = Neighbors {grid: &self.grid, positions: self.neighbors[(x, y)].iter()};
+            let updated = (self.rule)(cell, neighbors);
+            if updated != *cell {
+                changed += 1;
+            }
+            self.next[(x, y)] = updated;
+        }
+
+        std::mem::swap(&mut self.grid, &mut self.next);
+        self.generation += 1;
+        changed
+    }
+
+    // Advance count generations, returns the total number of cell changes
+    #[cfg(test)]
+    pub fn step_n(&mut self, count: usize) -> usize {
+        (0..count).map(|_| self.step()).sum()
+    }
+
+    // Step until a generation changes nothing, returns the steps taken
+    // including that last one
+    pub fn run_until_stable(&mut self) -> usize {
+        let start = self.generation;
+        while self.step() != 0 {}
+        self.generation - start
+    }
+
+    pub fn count<P>(&self, predicate: P) -> usize
+    where P: Fn(&T) -> bool {
+        self.grid.iter().filter(|x| predicate(x)).count()
+    }
+
+    // Step until a grid repeats, returns (first generation of the cycle, cycle length)
+    #[cfg(test)]
+    pub fn find_cycle(&mut self) -> (usize, usize)
+    where T: Hash + Eq {
+        let mut seen: HashMap<Grid<T>, usize> = HashMap::new();
+        loop {
+            if let Some(first) = seen.insert(self.grid.clone(), self.generation) {
+                return (first, self.generation - first);
+            }
+            self.step();
+        }
+    }
+}
+
+// Conway style automaton on an unbounded space of positions P (2D, 3D, hex, ...),
+// only the live cells are stored. The rule gets whether a cell is alive and
+// how many live neighbors it has, only cells next to a live cell are considered
+pub struct SparseAutomaton<P, N, R> {
+    live: HashSet<P>,
+    neighborhood: N,
+    rule: R,
+    generation: usize,
+}
+
+impl<P, N, I, R> SparseAutomaton<P, N, R>
+where P: Clone + Eq + Hash, N: Fn(&P) -> I, I: IntoIterator<Item = P>, R: Fn(bool, usize) -> bool {
+    pub fn new(live: HashSet<P>, neighborhood: N, rule: R) -> SparseAutomaton<P, N, R> {
+        SparseAutomaton {live, neighborhood, rule, generation: 0}
+    }
+
+    pub fn live(&self) -> &HashSet<P> {
+        &self.live
+    }
+
+    pub fn generation(&self) -> usize {
+        self.generation
+    }
+
+    // Advance one generation, returns how many cells changed
+    pub fn step(&mut self) -> usize {
+        let mut counts: HashMap<P, usize> = HashMap::new();
+        for cell in &self.live {
+            for neighbor in (self.neighborhood)(cell) {
+                *counts.entry(neighbor).or_insert(0) += 1;
+            }
+        }
+
+        // Live cells without live neighbors still need the rule applied
+        for cell in &self.live {
+            counts.entry(cell.clone()).or_insert(0);
+        }
+
+        let mut next: HashSet<P> = HashSet::new();
+        let mut changed = 0;
+        for (cell, count) in counts {
+            let alive = self.live.contains(&cell);
+            let becomes = (self.rule)(alive, count);
+            if alive != becomes {
+                changed += 1;
+            }
+            if becomes {
+                next.insert(cell);
+            }
+        }
+
+        self.live = next;
+        self.generation += 1;
+        changed
+    }
+
+    #[cfg(test)]
+    pub fn step_n(&mut self, count: usize) -> usize {
+        (0..count).map(|_| self.step()).sum()
+    }
+
+    #[cfg(test)]
+    pub fn run_until_stable(&mut self) -> usize {
+        let start = self.generation;
+        while self.step() != 0 {}
+        self.generation - start
+    }
+
+    // Step until the live set repeats, returns (first generation of the cycle, cycle length)
+    #[cfg(test)]
+    pub fn find_cycle(&mut self) -> (usize, usize)
+    where P: Ord {
+        let mut seen: HashMap<Vec<P>, usize> = HashMap::new();
+        loop {
+            let mut state: Vec<P> = self.live.iter().cloned().collect();
+            state.sort();
+            if let Some(first) = seen.insert(state, self.generation) {
+                return (first, self.generation - first);
+            }
+            self.step();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Standard Game of Life rule: live cells survive with 2 or 3 neighbors, dead
+    // cells are born with exactly 3
+    fn conway(alive: bool, live_neighbors: usize) -> bool {
+        live_neighbors == 3 || (alive && live_neighbors == 2)
+    }
+
+    fn life_rule(cell: &char, neighbors: Neighbors<char>) -> char {
+        let alive = neighbors.filter(|x| **x == '#').count();
+        if conway(*cell == '#', alive) { '#' } else { '.' }
+    }
+
+    #[test]
+    fn grid_automaton_test() {
+        let blinker = Grid::parse(&[".....", "..#..", "..#..", "..#..", "....."]).unwrap();
+        let mut automaton = GridAutomaton::with_neighborhood(blinker, |g, x, y| g.neighbors8(x, y).collect(), life_rule);
+
+        assert_eq!(4, automaton.step());
+        assert_eq!(".....\n.....\n.###.\n.....\n.....", automaton.grid().to_string());
+        assert_eq!(3, automaton.count(|x| *x == '#'));
+        assert_eq!(8, automaton.step_n(2));
+        assert_eq!(3, automaton.generation());
+        assert_eq!((3, 2), automaton.find_cycle());
+
+        let block = Grid::parse(&["....", ".##.", ".##.", "...."]).unwrap();
+        let mut automaton = GridAutomaton::with_neighborhood(block, |g, x, y| g.neighbors8(x, y).collect(), life_rule);
+        assert_eq!(1, automaton.run_until_stable());
+        assert_eq!((1, 1), automaton.find_cycle());
+    }
+
+    #[test]
+    fn sparse_automaton_3d_test() {
+        let neighborhood = |(x, y, z): &(i32, i32, i32)| {
+            let mut neighbors: Vec<(i32, i32, i32)> = Vec::new();
+            for dx in -1..2 {
+                for dy in -1..2 {
+                    for dz in -1..2 {
+                        if (dx, dy, dz) != (0, 0, 0) {
+                            neighbors.push((x + dx, y + dy, z + dz));
+                        }
+                    }
+                }
+            }
+            neighbors
+        };
+
+        // Glider from 2020 day 17, 112 cubes are active after 6 cycles
+        let grid = Grid::parse(&[".#.", "..#", "###"]).unwrap();
+        let live: HashSet<(i32, i32, i32)> = grid
+            .positions()
+            .filter(|(_, cell)| **cell == '#')
+            .map(|((x, y), _)| (x as i32, y as i32, 0))
+            .collect();
+        let mut automaton = SparseAutomaton::new(live, neighborhood, conway);
+        automaton.step_n(6);
+        assert_eq!(112, automaton.live().len());
+    }
+
+    #[test]
+    fn sparse_automaton_cycle_test() {
+        let neighborhood = |(x, y): &(i32, i32)| {
+            crate::common::grid::NEIGHBORS8.iter().map(|(dx, dy)| (x + *dx as i32, y + *dy as i32)).collect::<Vec<(i32, i32)>>()
+        };
+        let blinker: HashSet<(i32, i32)> = vec![(0, 0), (1, 0), (2, 0)].into_iter().collect();
+        let mut automaton = SparseAutomaton::new(blinker, neighborhood, conway);
+        assert_eq!((0, 2), automaton.find_cycle());
+
+        let lonely: HashSet<(i32, i32)> = vec![(0, 0)].into_iter().collect();
+        let mut automaton = SparseAutomaton::new(lonely, neighborhood, conway);
+        assert_eq!(1, automaton.step());
+        assert_eq!(0, automaton.live().len());
+        assert_eq!(1, automaton.run_until_stable());
+    }
+}
@@ -0,0 +1,325 @@
+// Bitsets and 01X style bit masks
+
+use std::str::FromStr;
+
+// Set of small integers 0..64 packed into a single word
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug, Default)]
+pub struct FixedBitSet {
+    word: u64
+}
+
+impl FixedBitSet {
+    pub fn new() -> FixedBitSet {
+        FixedBitSet {word: 0}
+    }
+
+    #[cfg(test)]
+    pub fn from_word(word: u64) -> FixedBitSet {
+        FixedBitSet {word}
+    }
+
+    // Every value in 0..len
+    pub fn full(len: u32) -> FixedBitSet {
+        assert!(len <= 64, "FixedBitSet holds at most 64 values, asked for {}", len);
+        FixedBitSet {word: if len == 64 { u64::MAX } else { (1 << len) - 1 }}
+    }
+
+    #[cfg(test)]
+    pub fn word(&self) -> u64 {
+        self.word
+    }
+
+    pub fn insert(&mut self, value: u32) {
+        assert!(value < 64, "FixedBitSet holds values 0..64, got {}", value);
+        self.word |= 1 << value;
+    }
+
+    #[cfg(test)]
+    pub fn remove(&mut self, value: u32) {
+        assert!(value < 64, "FixedBitSet holds values 0..64, got {}", value);
+        self.word &= !(1 << value);
+    }
+
+    #[cfg(test)]
+    pub fn contains(&self, value: u32) -> bool {
+        value < 64 && self.word & (1 << value) != 0
+    }
+
+    pub fn len(&self) -> u32 {
+        self.word.count_ones()
+    }
+
+    #[cfg(test)]
+    pub fn is_empty(&self) -> bool {
+        self.word == 0
+    }
+
+    pub fn union(&self, other: &FixedBitSet) -> FixedBitSet {
+        FixedBitSet {word: self.word | other.word}
+    }
+
+    pub fn intersection(&self, other: &FixedBitSet) -> FixedBitSet {
+        FixedBitSet {word: self.word & other.word}
+    }
+
+    #[cfg(test)]
+    pub fn difference(&self, other: &FixedBitSet) -> FixedBitSet {
+        FixedBitSet {word: self.word & !other.word}
+    }
+
+    // Values in ascending order
+    #[cfg(test)]
+    pub fn iter(&self) -> Ones {
+        Ones {word: self.word, offset: 0}
+    }
+}
+
+// Set bit positions of a word, lowest first, plus offset
+#[cfg(test)]
+pub struct Ones {
+    word: u64,
+    offset: usize
+}
+
+#[cfg(test)]
+impl Iterator for Ones {
+    type Item = usize;
+
+    fn next(&mut self) -> Option<usize> {
+        if self.word == 0 {
+            return None;
+        }
+        let bit = self.word.trailing_zeros() as usize;
+        // Clear the lowest set bit
+        self.word &= self.word - 1;
+        Some(self.offset + bit)
+    }
+}
+
+// Set of non negative integers that grows to fit the largest one inserted
+#[cfg(test)]
+#[derive(Clone, PartialEq, Eq, Hash, Debug, Default)]
+pub struct BitSet {
+    words: Vec<u64>
+}
+
+#[cfg(test)]
+impl BitSet {
+    pub fn new() -> BitSet {
+        BitSet {words: Vec::new()}
+    }
+
+    pub fn with_capacity(bits: usize) -> BitSet {
+        BitSet {words: vec![0; bits.div_ceil(64)]}
+    }
+
+    pub fn insert(&mut self, value: usize) {
+        let word = value / 64;
+        if word >= self.words.len() {
+            self.words.resize(word + 1, 0);
+        }
+        self.words[word] |= 1 << (value % 64);
+    }
+
+    pub fn remove(&mut self, value: usize) {
+        if let Some(word) = self.words.get_mut(value / 64) {
+            *word &= !(1 << (value % 64));
+        }
+    }
+
+    pub fn contains(&self, value: usize) -> bool {
+        self.words.get(value / 64).is_some_and(|word| word & (1 << (value % 64)) != 0)
+    }
+
+    pub fn len(&self) -> usize {
+        self.words.iter().map(|x| x.count_ones() as usize).sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.words.iter().all(|x| *x == 0)
+    }
+
+    pub fn union(&self, other: &BitSet) -> BitSet {
+        let length = self.words.len().max(other.words.len());
+        let word = |words: &Vec<u64>, i: usize| words.get(i).copied().unwrap_or(0);
+        BitSet {words: (0..length).map(|i| word(&self.words, i) | word(&other.words, i)).collect()}
+    }
+
+    pub fn intersection(&self, other: &BitSet) -> BitSet {
+        BitSet {words: self.words.iter().zip(other.words.iter()).map(|(a, b)| a & b).collect()}
+    }
+
+    pub fn difference(&self, other: &BitSet) -> BitSet {
+        let words = self.words
+            .iter()
+            .enumerate()
+            .map(|(i, a)| a & !other.words.get(i).copied().unwrap_or(0))
+            .collect();
+        BitSet {words}
+    }
+
+    // Values in ascending order
+    pub fn iter(&self) -> impl Iterator<Item = usize> + '_ {
+        self.words
+            .iter()
+            .enumerate()
+            .flat_map(|(i, word)| Ones {word: *word, offset: i * 64})
+    }
+}
+
+// A mask like "X1001X", most significant bit first, split into one word per
+// kind of character
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug, Default)]
+pub struct Mask {
+    pub ones: u64,
+    pub zeros: u64,
+    pub floating: u64
+}
+
+impl Mask {
+    // Force the 1s and 0s, floating bits keep the value's bit
+    pub fn apply(&self, value: u64) -> u64 {
+        (value | self.ones) & !self.zeros
+    }
+
+    // Force the 1s, every combination of the floating bits, 0s keep the value's bit
+    pub fn float(&self, value: u64) -> impl Iterator<Item = u64> {
+        let base = (value | self.ones) & !self.floating;
+        subsets(self.floating).map(move |x| base | x)
+    }
+}
+
+impl FromStr for Mask {
+    type Err = String;
+
+    fn from_str(text: &str) -> Result<Mask, String> {
+        let text = text.trim();
+        if text.len() > 64 {
+            return Err(format!("Mask {} is wider than 64 bits", text));
+        }
+
+        let mut mask = Mask::default();
+        for character in text.chars() {
+            mask.ones <<= 1;
+            mask.zeros <<= 1;
+            mask.floating <<= 1;
+            match character {
+                '1' => mask.ones |= 1,
+                '0' => mask.zeros |= 1,
+                'X' => mask.floating |= 1,
+                _ => return Err(format!("Unexpected '{}' in mask {}", character, text))
+            }
+        }
+        Ok(mask)
+    }
+}
+
+// Every subset of the bits in mask, from mask itself down to 0
+pub fn subsets(mask: u64) -> Subsets {
+    Subsets {mask, next: Some(mask)}
+}
+
+pub struct Subsets {
+    mask: u64,
+    next: Option<u64>
+}
+
+impl Iterator for Subsets {
+    type Item = u64;
+
+    // Subtracting one and masking gives the next smaller subset
+    fn next(&mut self) -> Option<u64> {
+        let current = self.next?;
+        self.next = if current == 0 { None } else { Some((current - 1) & self.mask) };
+        Some(current)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fixed_bit_set_test() {
+        let mut a = FixedBitSet::new();
+        a.insert(0);
+        a.insert(2);
+        a.insert(63);
+        assert_eq!(3, a.len());
+        assert!(a.contains(63) && !a.contains(1) && !a.contains(64));
+        assert_eq!(vec![0, 2, 63], a.iter().collect::<Vec<usize>>());
+
+        let b = FixedBitSet::from_word(0b110);
+        assert_eq!(0b110 | 1 | 1 << 63, a.union(&b).word());
+        assert_eq!(vec![2], a.intersection(&b).iter().collect::<Vec<usize>>());
+        assert_eq!(vec![0, 63], a.difference(&b).iter().collect::<Vec<usize>>());
+
+        a.remove(63);
+        assert_eq!(2, a.len());
+        assert_eq!(26, FixedBitSet::full(26).len());
+        assert_eq!(u64::MAX, FixedBitSet::full(64).word());
+        assert!(FixedBitSet::new().is_empty());
+    }
+
+    #[test]
+    #[should_panic(expected = "FixedBitSet holds values 0..64, got 64")]
+    fn fixed_bit_set_insert_out_of_range_test() {
+        FixedBitSet::new().insert(64);
+    }
+
+    #[test]
+    #[should_panic(expected = "FixedBitSet holds values 0..64, got 70")]
+    fn fixed_bit_set_remove_out_of_range_test() {
+        FixedBitSet::full(64).remove(70);
+    }
+
+    #[test]
+    fn bit_set_test() {
+        let mut a = BitSet::new();
+        a.insert(3);
+        a.insert(130);
+        assert!(a.contains(130) && !a.contains(129) && !a.contains(10000));
+        assert_eq!(vec![3, 130], a.iter().collect::<Vec<usize>>());
+
+        let mut b = BitSet::with_capacity(10);
+        b.insert(3);
+        b.insert(4);
+        assert_eq!(vec![3, 4, 130], a.union(&b).iter().collect::<Vec<usize>>());
+        assert_eq!(vec![3], a.intersection(&b).iter().collect::<Vec<usize>>());
+        assert_eq!(vec![130], a.difference(&b).iter().collect::<Vec<usize>>());
+        assert_eq!(vec![4], b.difference(&a).iter().collect::<Vec<usize>>());
+
+        a.remove(130);
+        a.remove(5000);
+        assert_eq!(1, a.len());
+        assert!(!a.is_empty());
+        a.remove(3);
+        assert!(a.is_empty());
+    }
+
+    #[test]
+    fn mask_test() {
+        let mask: Mask = "XXXXXXXXXXXXXXXXXXXXXXXXXXXXX1XXXX0X".parse().unwrap();
+        assert_eq!(1 << 6, mask.ones);
+        assert_eq!(1 << 1, mask.zeros);
+        assert_eq!(34, mask.floating.count_ones());
+        assert_eq!(73, mask.apply(11));
+        assert_eq!(101, mask.apply(101));
+        assert_eq!(64, mask.apply(0));
+
+        let mask: Mask = "000000000000000000000000000000X1001X".parse().unwrap();
+        let mut addresses: Vec<u64> = mask.float(42).collect();
+        addresses.sort();
+        assert_eq!(vec![26, 27, 58, 59], addresses);
+
+        assert!("X12".parse::<Mask>().is_err());
+        assert!("X".repeat(65).parse::<Mask>().is_err());
+    }
+
+    #[test]
+    fn subsets_test() {
+        assert_eq!(vec![0b101, 0b100, 0b001, 0], subsets(0b101).collect::<Vec<u64>>());
+        assert_eq!(vec![0], subsets(0).collect::<Vec<u64>>());
+        assert_eq!(1 << 10, subsets(0b1111111111 << 20).count());
+    }
+}
@@ -1,35 +1,123 @@
 use std::{
     vec::Vec,
+    collections::HashMap,
+    fmt,
+    str::FromStr,
 };
 
-pub fn vector_str_to_int(vector: Vec<String>) -> Vec<i16> {
-    return vector.into_iter().map(|x| x.parse::<i16>().unwrap()).collect();
+// A value that failed to parse, line and field are 1-based, field is only
+// set when the line was split into several values
+#[derive(Debug, PartialEq)]
+pub struct ParseError {
+    pub line: usize,
+    pub field: Option<usize>,
+    pub token: String,
+    pub message: String,
 }
 
-pub fn vector_str_to_int64(vector: Vec<String>) -> Vec<i64> {
-    return vector.into_iter().map(|x| x.parse::<i64>().unwrap()).collect();
+impl ParseError {
+    pub fn new(line: usize, field: Option<usize>, token: &str, message: impl fmt::Display) -> ParseError {
+        ParseError {line, field, token: token.to_string(), message: message.to_string()}
+    }
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.field {
+            Some(field) => write!(f, "line {} field {}: could not parse '{}': {}", self.line, field, self.token, self.message),
+            None => write!(f, "line {}: could not parse '{}': {}", self.line, self.token, self.message),
+        }
+    }
+}
+
+// Parse every line into a T, failing on the first line that does not parse
+pub fn parse_lines<T, S>(lines: &[S]) -> Result<Vec<T>, ParseError>
+where T: FromStr, T::Err: fmt::Display, S: AsRef<str> {
+    let mut values: Vec<T> = Vec::with_capacity(lines.len());
+
+    for (index, line) in lines.iter().enumerate() {
+        let token = line.as_ref().trim();
+        match token.parse::<T>() {
+            Ok(value) => values.push(value),
+            Err(error) => return Err(ParseError::new(index + 1, None, token, error)),
+        }
+    }
+
+    Ok(values)
+}
+
+// Parse already split fields of the given 1-based line, errors carry the line
+// and the position of the field among those given
+pub fn parse_fields<'a, T>(line: usize, fields: impl Iterator<Item = &'a str>) -> Result<Vec<T>, ParseError>
+where T: FromStr, T::Err: fmt::Display {
+    let mut values: Vec<T> = Vec::new();
+
+    for (index, field) in fields.enumerate() {
+        match field.parse::<T>() {
+            Ok(value) => values.push(value),
+            Err(error) => return Err(ParseError::new(line, Some(index + 1), field, error)),
+        }
+    }
+
+    Ok(values)
+}
+
+// Parse line number `number` of values split on separator, empty fields are skipped
+pub fn parse_separated<T>(number: usize, line: &str, separator: char) -> Result<Vec<T>, ParseError>
+where T: FromStr, T::Err: fmt::Display {
+    parse_fields(number, line.split(separator).map(|x| x.trim()).filter(|x| !x.is_empty()))
+}
+
+pub fn parse_comma_separated<T>(number: usize, line: &str) -> Result<Vec<T>, ParseError>
+where T: FromStr, T::Err: fmt::Display {
+    parse_separated(number, line, ',')
 }
 
-pub fn chunk_parts(lines: &Vec<String>) -> Vec<String> {
-    let mut chunks: Vec<String> = Vec::new();
-    let mut chunk = Vec::<String>::new();
+pub fn parse_whitespace_separated<T>(number: usize, line: &str) -> Result<Vec<T>, ParseError>
+where T: FromStr, T::Err: fmt::Display {
+    parse_fields(number, line.split_whitespace())
+}
+
+// Group lines separated by blank lines, each group keeps its own lines
+pub fn split_records<S: AsRef<str>>(lines: &[S]) -> Vec<Vec<&str>> {
+    let mut records: Vec<Vec<&str>> = Vec::new();
+    let mut record: Vec<&str> = Vec::new();
 
     for line in lines {
-        if line.is_empty() {
-            let contig_chunk = chunk.join(" ");
-            chunks.push(contig_chunk);
-            chunk = Vec::<String>::new();
+        let line = line.as_ref();
+        if line.trim().is_empty() {
+            if !record.is_empty() {
+                records.push(record);
+                record = Vec::new();
+            }
             continue;
         }
 
-        chunk.push(line.to_string());
+        record.push(line);
     }
 
-    if !chunk.is_empty() {
-        chunks.push(chunk.join(" "));
+    if !record.is_empty() {
+        records.push(record);
     }
 
-    return chunks;
+    records
+}
+
+// Parse a passport style record of whitespace separated key:value fields spread
+// over one or more lines, a field without a ':' is reported with its position
+pub fn parse_key_values<'a>(record: &[&'a str]) -> Result<HashMap<&'a str, &'a str>, ParseError> {
+    let mut fields: HashMap<&str, &str> = HashMap::new();
+
+    for (index, line) in record.iter().enumerate() {
+        for (field, token) in line.split_whitespace().enumerate() {
+            match token.find(':') {
+                Some(split) => fields.insert(&token[..split], &token[split + 1..]),
+                None => return Err(ParseError::new(index + 1, Some(field + 1), token, "expected key:value")),
+            };
+        }
+    }
+
+    Ok(fields)
 }
 
 #[cfg(test)]
@@ -37,8 +125,38 @@ mod tests {
     use super::*;
 
     #[test]
-    fn chunk_parts_test() {
-        let lines = vec![        
+    fn parse_lines_test() {
+        let lines: Vec<String> = ["1721", " 979", "40000"].iter().map(|x| x.to_string()).collect();
+        assert_eq!(vec![1721, 979, 40000], parse_lines::<i32, String>(&lines).unwrap());
+        assert_eq!(vec![1721, 979, 40000], parse_lines::<i64, &str>(&["1721", "979", "40000"]).unwrap());
+
+        let error = parse_lines::<i16, String>(&lines).unwrap_err();
+        assert_eq!(3, error.line);
+        assert_eq!(None, error.field);
+        assert_eq!("40000", error.token);
+        assert_eq!("line 3: could not parse '40000': number too large to fit in target type", error.to_string());
+    }
+
+    #[test]
+    fn parse_separated_test() {
+        assert_eq!(vec![0, 3, 6], parse_comma_separated::<i64>(1, "0,3,6").unwrap());
+        assert_eq!(vec![7, 13, 59], parse_comma_separated::<i32>(1, "7,13,,,59,").unwrap());
+        assert_eq!(vec![1, 2, 3], parse_whitespace_separated::<u8>(1, " 1  2\t3 ").unwrap());
+        assert_eq!(vec!["a".to_string(), "b".to_string()], parse_separated::<String>(1, "a | b", '|').unwrap());
+
+        let error = parse_comma_separated::<i32>(2, "7,13,x,59").unwrap_err();
+        assert_eq!(2, error.line);
+        assert_eq!(Some(3), error.field);
+        assert_eq!("x", error.token);
+        assert_eq!("line 2 field 3: could not parse 'x': invalid digit found in string", error.to_string());
+
+        let error = parse_fields::<u8>(5, vec!["1", "300"].into_iter()).unwrap_err();
+        assert_eq!((5, Some(2)), (error.line, error.field));
+    }
+
+    #[test]
+    fn split_records_test() {
+        let lines: Vec<String> = vec![
             "ecl:gry pid:860033327 eyr:2020 hcl:#fffffd",
             "byr:1937 iyr:2017 cid:147 hgt:183cm",
             "",
@@ -53,11 +171,32 @@ mod tests {
             "hcl:#cfa07d eyr:2025 pid:166559648",
             "iyr:2011 ecl:brn hgt:59in"]
             .iter().map(|x| x.to_string()).collect();
-        
-        let chunked_lines = chunk_parts(&lines);
-        assert_eq!("ecl:gry pid:860033327 eyr:2020 hcl:#fffffd byr:1937 iyr:2017 cid:147 hgt:183cm", chunked_lines.get(0).unwrap());
-        assert_eq!("iyr:2013 ecl:amb cid:350 eyr:2023 pid:028048884 hcl:#cfa07d byr:1929", chunked_lines.get(1).unwrap());
-        assert_eq!("hcl:#ae17e1 iyr:2013 eyr:2024 ecl:brn pid:760753108 byr:1931 hgt:179cm", chunked_lines.get(2).unwrap());
-        assert_eq!("hcl:#cfa07d eyr:2025 pid:166559648 iyr:2011 ecl:brn hgt:59in", chunked_lines.get(3).unwrap());
+
+        let records = split_records(&lines);
+        assert_eq!(4, records.len());
+        assert_eq!(vec!["ecl:gry pid:860033327 eyr:2020 hcl:#fffffd", "byr:1937 iyr:2017 cid:147 hgt:183cm"], records[0]);
+        assert_eq!(vec!["iyr:2013 ecl:amb cid:350 eyr:2023 pid:028048884", "hcl:#cfa07d byr:1929"], records[1]);
+        assert_eq!(vec!["hcl:#ae17e1 iyr:2013", "eyr:2024", "ecl:brn pid:760753108 byr:1931", "hgt:179cm"], records[2]);
+        assert_eq!(vec!["hcl:#cfa07d eyr:2025 pid:166559648", "iyr:2011 ecl:brn hgt:59in"], records[3]);
+
+        assert_eq!(vec![vec!["ab", "a c"], vec!["b"]], split_records(&["ab", "a c", "", "b"]));
+    }
+
+    #[test]
+    fn split_records_repeated_blank_lines_test() {
+        let lines = ["", "a", "", "", "b c", "d", " ", "", "e", ""];
+        assert_eq!(vec![vec!["a"], vec!["b c", "d"], vec!["e"]], split_records(&lines));
+    }
+
+    #[test]
+    fn parse_key_values_test() {
+        let fields = parse_key_values(&["ecl:gry pid:860033327", "hcl:#fffffd"]).unwrap();
+        assert_eq!(3, fields.len());
+        assert_eq!("gry", fields["ecl"]);
+        assert_eq!("#fffffd", fields["hcl"]);
+
+        let error = parse_key_values(&["ecl:gry", "pid:1 hcl"]).unwrap_err();
+        assert_eq!(2, error.line);
+        assert_eq!(Some(2), error.field);
     }
 }
\ No newline at end of file
@@ -0,0 +1,142 @@
+// Helpers for deterministic state machines that eventually repeat. The step
+// function must be pure and the sequence must cycle at some point (a fixpoint
+// is a cycle of length 1), otherwise these never return.
+// Floyd is kept next to Brent mostly as a cross check
+
+// States from step start onwards repeat every length steps
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct Cycle {
+    pub start: usize,
+    pub length: usize
+}
+
+impl Cycle {
+    // Earliest step with the same state as step n
+    #[cfg(test)]
+    pub fn reduce(&self, n: usize) -> usize {
+        if n < self.start {
+            return n;
+        }
+        self.start + (n - self.start) % self.length
+    }
+}
+
+// Floyd's tortoise and hare, the hare moves two steps for every one of the tortoise
+#[cfg(test)]
+pub fn floyd<S, F>(initial: S, step: F) -> Cycle
+where S: Clone + PartialEq, F: Fn(&S) -> S {
+    let mut tortoise = step(&initial);
+    let mut hare = step(&tortoise);
+    while tortoise != hare {
+        tortoise = step(&tortoise);
+        hare = step(&step(&hare));
+    }
+
+    // The distance from the start to the meeting point is a multiple of the
+    // cycle length, restart the tortoise and move both at the same pace
+    let mut start = 0;
+    tortoise = initial;
+    while tortoise != hare {
+        tortoise = step(&tortoise);
+        hare = step(&hare);
+        start += 1;
+    }
+
+    let mut length = 1;
+    hare = step(&tortoise);
+    while tortoise != hare {
+        hare = step(&hare);
+        length += 1;
+    }
+
+    Cycle {start, length}
+}
+
+// Brent's algorithm, finds the length first by teleporting the tortoise to the
+// hare at every power of two. Fewer step calls than Floyd
+pub fn brent<S, F>(initial: S, step: F) -> Cycle
+where S: Clone + PartialEq, F: Fn(&S) -> S {
+    let mut power = 1;
+    let mut length = 1;
+    let mut tortoise = initial.clone();
+    let mut hare = step(&initial);
+    while tortoise != hare {
+        if power == length {
+            tortoise = hare.clone();
+            power *= 2;
+            length = 0;
+        }
+        hare = step(&hare);
+        length += 1;
+    }
+
+    // Hare starts length steps ahead, they meet at the start of the cycle
+    let mut start = 0;
+    tortoise = initial.clone();
+    hare = initial;
+    for _ in 0..length {
+        hare = step(&hare);
+    }
+    while tortoise != hare {
+        tortoise = step(&tortoise);
+        hare = step(&hare);
+        start += 1;
+    }
+
+    Cycle {start, length}
+}
+
+// State after n steps, only stepping through the first pass of the cycle
+#[cfg(test)]
+pub fn nth<S, F>(initial: S, step: F, n: usize) -> S
+where S: Clone + PartialEq, F: Fn(&S) -> S {
+    let cycle = brent(initial.clone(), &step);
+    let target = cycle.reduce(n);
+    log::debug!("Step {} is the same as step {} in {:?}", n, target, cycle);
+
+    let mut state = initial;
+    for _ in 0..target {
+        state = step(&state);
+    }
+    state
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cycle_test() {
+        // 2, 4, 16, 256, 536, 296, ..., 136, 496 and back to 16
+        let square = |x: &u64| x * x % 1000;
+        let expected = Cycle {start: 2, length: 20};
+        assert_eq!(expected, floyd(2, square));
+        assert_eq!(expected, brent(2, square));
+
+        // Fixpoint
+        let halve = |x: &u64| x / 2;
+        assert_eq!(Cycle {start: 7, length: 1}, brent(100, halve));
+        assert_eq!(Cycle {start: 7, length: 1}, floyd(100, halve));
+
+        // Pure cycle
+        let next = |x: &u64| (x + 1) % 5;
+        assert_eq!(Cycle {start: 0, length: 5}, brent(3, next));
+        assert_eq!(Cycle {start: 0, length: 5}, floyd(3, next));
+    }
+
+    #[test]
+    fn nth_test() {
+        let square = |x: &u64| x * x % 1000;
+        let cycle = brent(2, square);
+        assert_eq!(1, cycle.reduce(1));
+        assert_eq!(2, cycle.reduce(22));
+        assert_eq!(3, cycle.reduce(1_000_000_003));
+
+        let mut state = 2;
+        for n in 0..60 {
+            assert_eq!(state, nth(2, square, n));
+            state = square(&state);
+        }
+        assert_eq!(256, nth(2, square, 1_000_000_003));
+    }
+}
@@ -0,0 +1,254 @@
+use std::collections::HashMap;
+use std::hash::Hash;
+
+// Disjoint set union over 0..len. Union by rank keeps the trees shallow and
+// find flattens every path it walks, so both are close to constant time
+#[derive(Clone, Debug)]
+pub struct Dsu {
+    parent: Vec<usize>,
+    rank: Vec<u8>,
+    size: Vec<usize>,
+    sets: usize
+}
+
+impl Dsu {
+    // Every element starts in a set of its own
+    pub fn new(len: usize) -> Dsu {
+        Dsu {parent: (0..len).collect(), rank: vec![0; len], size: vec![1; len], sets: len}
+    }
+
+    // Add a new singleton set, returns its element
+    pub fn add(&mut self) -> usize {
+        let element = self.parent.len();
+        self.parent.push(element);
+        self.rank.push(0);
+        self.size.push(1);
+        self.sets += 1;
+        element
+    }
+
+    pub fn len(&self) -> usize {
+        self.parent.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.parent.is_empty()
+    }
+
+    // Representative of the set holding element
+    pub fn find(&mut self, element: usize) -> usize {
+        let mut root = element;
+        while self.parent[root] != root {
+            root = self.parent[root];
+        }
+
+        // Point everything on the way straight at the root
+        let mut current = element;
+        while self.parent[current] != root {
+            let next = self.parent[current];
+            self.parent[current] = root;
+            current = next;
+        }
+        root
+    }
+
+    // Merge the sets holding a and b, false if they were already the same set
+    pub fn union(&mut self, a: usize, b: usize) -> bool {
+        let mut a = self.find(a);
+        let mut b = self.find(b);
+        if a == b {
+            return false;
+        }
+
+        if self.rank[a] < self.rank[b] {
+            std::mem::swap(&mut a, &mut b);
+        }
+        self.parent[b] = a;
+        self.size[a] += self.size[b];
+        if self.rank[a] == self.rank[b] {
+            self.rank[a] += 1;
+        }
+        self.sets -= 1;
+        true
+    }
+
+    pub fn same(&mut self, a: usize, b: usize) -> bool {
+        self.find(a) == self.find(b)
+    }
+
+    // Number of elements in the set holding element
+    pub fn set_size(&mut self, element: usize) -> usize {
+        let root = self.find(element);
+        self.size[root]
+    }
+
+    pub fn set_count(&self) -> usize {
+        self.sets
+    }
+
+    // Elements of every set, sets ordered by their smallest element and each
+    // set in ascending order
+    pub fn groups(&mut self) -> Vec<Vec<usize>> {
+        let mut index: HashMap<usize, usize> = HashMap::new();
+        let mut groups: Vec<Vec<usize>> = Vec::new();
+        for element in 0..self.len() {
+            let root = self.find(element);
+            let group = *index.entry(root).or_insert_with(|| {
+                groups.push(Vec::new());
+                groups.len() - 1
+            });
+            groups[group].push(element);
+        }
+        groups
+    }
+}
+
+// Dsu over arbitrary labels, each label is interned into an element the first
+// time it's seen
+#[derive(Clone, Debug)]
+pub struct LabelDsu<L> {
+    dsu: Dsu,
+    ids: HashMap<L, usize>,
+    labels: Vec<L>
+}
+
+impl<L> LabelDsu<L>
+where L: Clone + Eq + Hash {
+    pub fn new() -> LabelDsu<L> {
+        LabelDsu {dsu: Dsu::new(0), ids: HashMap::new(), labels: Vec::new()}
+    }
+
+    // Element for label, adding it as a singleton set if it's new
+    pub fn id(&mut self, label: &L) -> usize {
+        if let Some(id) = self.ids.get(label) {
+            return *id;
+        }
+        let id = self.dsu.add();
+        self.ids.insert(label.clone(), id);
+        self.labels.push(label.clone());
+        id
+    }
+
+    pub fn len(&self) -> usize {
+        self.labels.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.labels.is_empty()
+    }
+
+    // Representative label of the set holding label, None if never seen
+    pub fn find(&mut self, label: &L) -> Option<&L> {
+        let id = *self.ids.get(label)?;
+        let root = self.dsu.find(id);
+        Some(&self.labels[root])
+    }
+
+    pub fn union(&mut self, a: &L, b: &L) -> bool {
+        let a = self.id(a);
+        let b = self.id(b);
+        self.dsu.union(a, b)
+    }
+
+    // Labels never seen are only the same as themselves
+    pub fn same(&mut self, a: &L, b: &L) -> bool {
+        match (self.ids.get(a).copied(), self.ids.get(b).copied()) {
+            (Some(a), Some(b)) => self.dsu.same(a, b),
+            _ => a == b
+        }
+    }
+
+    pub fn set_size(&mut self, label: &L) -> usize {
+        match self.ids.get(label).copied() {
+            Some(id) => self.dsu.set_size(id),
+            None => 1
+        }
+    }
+
+    pub fn set_count(&self) -> usize {
+        self.dsu.set_count()
+    }
+
+    // Labels of every set, in the order they were first seen
+    pub fn groups(&mut self) -> Vec<Vec<L>> {
+        let labels = &self.labels;
+        self.dsu
+            .groups()
+            .iter()
+            .map(|group| group.iter().map(|x| labels[*x].clone()).collect())
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn dsu_test() {
+        assert!(Dsu::new(0).is_empty());
+        let mut dsu = Dsu::new(6);
+        assert!(!dsu.is_empty());
+        assert_eq!(6, dsu.set_count());
+        assert!(dsu.union(0, 1));
+        assert!(dsu.union(2, 3));
+        assert!(dsu.union(1, 3));
+        assert!(!dsu.union(0, 2));
+        assert!(dsu.same(0, 3));
+        assert!(!dsu.same(0, 4));
+        assert_eq!(4, dsu.set_size(2));
+        assert_eq!(1, dsu.set_size(5));
+        assert_eq!(3, dsu.set_count());
+        assert_eq!(vec![vec![0, 1, 2, 3], vec![4], vec![5]], dsu.groups());
+
+        assert_eq!(6, dsu.add());
+        assert!(dsu.union(6, 5));
+        assert_eq!(vec![vec![0, 1, 2, 3], vec![4], vec![5, 6]], dsu.groups());
+        assert_eq!(7, dsu.len());
+    }
+
+    #[test]
+    fn dsu_long_chain_test() {
+        let mut dsu = Dsu::new(100000);
+        for i in 1..100000 {
+            dsu.union(i - 1, i);
+        }
+        assert_eq!(1, dsu.set_count());
+        assert_eq!(100000, dsu.set_size(99999));
+        assert!(dsu.same(0, 99999));
+    }
+
+    #[test]
+    fn label_dsu_test() {
+        // Regions of the same letter in a small grid
+        let grid = ["aab", "abb", "cca"];
+        let mut regions: LabelDsu<(usize, usize)> = LabelDsu::new();
+        for (y, row) in grid.iter().enumerate() {
+            let row: Vec<char> = row.chars().collect();
+            for x in 0..row.len() {
+                regions.id(&(x, y));
+                if x > 0 && row[x - 1] == row[x] {
+                    regions.union(&(x - 1, y), &(x, y));
+                }
+                if y > 0 && grid[y - 1].chars().nth(x).unwrap() == row[x] {
+                    regions.union(&(x, y - 1), &(x, y));
+                }
+            }
+        }
+
+        assert_eq!(4, regions.set_count());
+        assert_eq!(3, regions.set_size(&(0, 0)));
+        assert!(regions.same(&(2, 0), &(1, 1)));
+        assert!(!regions.same(&(0, 0), &(2, 2)));
+        assert_eq!(vec![(2, 2)], regions.groups()[3]);
+
+        let mut names: LabelDsu<String> = LabelDsu::new();
+        assert!(names.is_empty());
+        assert_eq!(None, names.find(&"a".to_string()));
+        assert!(names.same(&"a".to_string(), &"a".to_string()));
+        names.union(&"a".to_string(), &"b".to_string());
+        assert!(names.find(&"b".to_string()).is_some());
+        assert_eq!(names.find(&"a".to_string()).cloned(), names.find(&"b".to_string()).cloned());
+        assert_eq!(2, names.len());
+    }
+}
@@ -0,0 +1,242 @@
+// Point/direction helpers, shared by the navigation style days
+
+use std::fmt;
+use std::ops::{Add, AddAssign, Mul, Neg, Sub, SubAssign};
+use num::Signed;
+
+// Point or vector on the plane, x grows east and y grows north.
+// Note common::grid counts y downwards, flip the sign when mixing the two
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug, Default)]
+pub struct Point2<T> {
+    pub x: T,
+    pub y: T
+}
+
+impl<T: Signed + Copy> Point2<T> {
+    pub fn new(x: T, y: T) -> Point2<T> {
+        Point2 {x, y}
+    }
+
+    pub fn origin() -> Point2<T> {
+        Point2::new(T::zero(), T::zero())
+    }
+
+    // Manhattan distance from the origin
+    pub fn manhattan(&self) -> T {
+        self.x.abs() + self.y.abs()
+    }
+
+    #[cfg(test)]
+    pub fn manhattan_to(&self, other: &Point2<T>) -> T {
+        (*self - *other).manhattan()
+    }
+
+    // 90 degrees counter clockwise about the origin
+    pub fn rotate_left(&self) -> Point2<T> {
+        Point2::new(-self.y, self.x)
+    }
+
+    // 90 degrees clockwise about the origin
+    #[cfg(test)]
+    pub fn rotate_right(&self) -> Point2<T> {
+        Point2::new(self.y, -self.x)
+    }
+
+    // Rotate about the origin by a multiple of 90 degrees, positive is counter
+    // clockwise, see https://matthew-brett.github.io/teaching/rotation_2d.html
+    pub fn rotate(&self, degrees: i64) -> Point2<T> {
+        let mut rotated = *self;
+        for _ in 0..quarter_turns(degrees) {
+            rotated = rotated.rotate_left();
+        }
+        rotated
+    }
+}
+
+// Number of counter clockwise quarter turns in 0..4 for an angle in degrees
+fn quarter_turns(degrees: i64) -> i64 {
+    assert!(degrees % 90 == 0, "Only right angle rotations are exact, got {} degrees", degrees);
+    (degrees / 90).rem_euclid(4)
+}
+
+impl<T: Signed + Copy> Add for Point2<T> {
+    type Output = Point2<T>;
+
+    fn add(self, other: Point2<T>) -> Point2<T> {
+        Point2::new(self.x + other.x, self.y + other.y)
+    }
+}
+
+impl<T: Signed + Copy> Sub for Point2<T> {
+    type Output = Point2<T>;
+
+    fn sub(self, other: Point2<T>) -> Point2<T> {
+        Point2::new(self.x - other.x, self.y - other.y)
+    }
+}
+
+impl<T: Signed + Copy> AddAssign for Point2<T> {
+    fn add_assign(&mut self, other: Point2<T>) {
+        *self = *self + other;
+    }
+}
+
+impl<T: Signed + Copy> SubAssign for Point2<T> {
+    fn sub_assign(&mut self, other: Point2<T>) {
+        *self = *self - other;
+    }
+}
+
+// Scale by a scalar
+impl<T: Signed + Copy> Mul<T> for Point2<T> {
+    type Output = Point2<T>;
+
+    fn mul(self, scale: T) -> Point2<T> {
+        Point2::new(self.x * scale, self.y * scale)
+    }
+}
+
+impl<T: Signed + Copy> Neg for Point2<T> {
+    type Output = Point2<T>;
+
+    fn neg(self) -> Point2<T> {
+        Point2::new(-self.x, -self.y)
+    }
+}
+
+impl<T: fmt::Display> fmt::Display for Point2<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "({}, {})", self.x, self.y)
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum Dir {
+    North,
+    East,
+    South,
+    West
+}
+
+// Clockwise from north
+const DIRS: [Dir; 4] = [Dir::North, Dir::East, Dir::South, Dir::West];
+
+impl Dir {
+    pub fn from_char(character: char) -> Option<Dir> {
+        match character {
+            'N' => Some(Dir::North),
+            'E' => Some(Dir::East),
+            'S' => Some(Dir::South),
+            'W' => Some(Dir::West),
+            _ => None
+        }
+    }
+
+    fn index(&self) -> i64 {
+        match self {
+            Dir::North => 0,
+            Dir::East => 1,
+            Dir::South => 2,
+            Dir::West => 3
+        }
+    }
+
+    pub fn turn_left(&self) -> Dir {
+        DIRS[(self.index() + 3) as usize % 4]
+    }
+
+    #[cfg(test)]
+    pub fn turn_right(&self) -> Dir {
+        DIRS[(self.index() + 1) as usize % 4]
+    }
+
+    #[cfg(test)]
+    pub fn reverse(&self) -> Dir {
+        DIRS[(self.index() + 2) as usize % 4]
+    }
+
+    // Turn by a multiple of 90 degrees, positive is counter clockwise like Point2::rotate
+    pub fn turn(&self, degrees: i64) -> Dir {
+        let mut turned = *self;
+        for _ in 0..quarter_turns(degrees) {
+            turned = turned.turn_left();
+        }
+        turned
+    }
+
+    // Unit step, north is +y
+    pub fn offset<T: Signed + Copy>(&self) -> Point2<T> {
+        match self {
+            Dir::North => Point2::new(T::zero(), T::one()),
+            Dir::East => Point2::new(T::one(), T::zero()),
+            Dir::South => Point2::new(T::zero(), -T::one()),
+            Dir::West => Point2::new(-T::one(), T::zero())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn point_ops_test() {
+        let a: Point2<i64> = Point2::new(10, 4);
+        let b = Point2::new(-3, 2);
+        assert_eq!(Point2::new(7, 6), a + b);
+        assert_eq!(Point2::new(13, 2), a - b);
+        assert_eq!(Point2::new(-30, 20), b * 10);
+        assert_eq!(Point2::new(3, -2), -b);
+        assert_eq!(14, a.manhattan());
+        assert_eq!(15, a.manhattan_to(&b));
+        assert_eq!(15, b.manhattan_to(&a));
+        assert_eq!(0, Point2::<i64>::origin().manhattan());
+
+        let mut c = a;
+        c += b;
+        c -= Point2::new(7, 0);
+        assert_eq!(Point2::new(0, 6), c);
+        assert_eq!("(0, 6)", c.to_string());
+    }
+
+    #[test]
+    fn point_rotation_test() {
+        let waypoint: Point2<i64> = Point2::new(10, 4);
+        assert_eq!(Point2::new(4, -10), waypoint.rotate_right());
+        assert_eq!(Point2::new(-4, 10), waypoint.rotate_left());
+        assert_eq!(waypoint, waypoint.rotate_left().rotate_right());
+        assert_eq!(waypoint.rotate_left(), waypoint.rotate(90));
+        assert_eq!(waypoint.rotate_right(), waypoint.rotate(-90));
+        assert_eq!(waypoint.rotate_right(), waypoint.rotate(270));
+        assert_eq!(Point2::new(-10, -4), waypoint.rotate(180));
+        assert_eq!(waypoint, waypoint.rotate(-720));
+    }
+
+    #[test]
+    #[should_panic]
+    fn point_rotation_not_right_angle_test() {
+        Point2::new(1, 0).rotate(45);
+    }
+
+    #[test]
+    fn dir_test() {
+        assert_eq!(Dir::North, Dir::East.turn_left());
+        assert_eq!(Dir::South, Dir::East.turn_right());
+        assert_eq!(Dir::West, Dir::East.reverse());
+        assert_eq!(Dir::North, Dir::East.turn(90));
+        assert_eq!(Dir::West, Dir::East.turn(180));
+        assert_eq!(Dir::South, Dir::East.turn(-90));
+        assert_eq!(Dir::West, Dir::North.turn(90));
+        assert_eq!(Dir::North, Dir::North.turn(360));
+        assert_eq!(Some(Dir::West), Dir::from_char('W'));
+        assert_eq!(None, Dir::from_char('F'));
+
+        for dir in DIRS.iter() {
+            let offset: Point2<i64> = dir.offset();
+            assert_eq!(dir.turn_left().offset::<i64>(), offset.rotate_left());
+            assert_eq!(dir.turn_right().offset::<i64>(), offset.rotate_right());
+            assert_eq!(dir.reverse().offset::<i64>(), -offset);
+            assert_eq!(*dir, dir.turn_left().turn_right());
+        }
+    }
+}
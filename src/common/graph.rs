@@ -0,0 +1,353 @@
+use std::collections::HashMap;
+use std::hash::Hash;
+
+// Traversals and shortest paths no day needs yet are only compiled for tests
+#[cfg(test)]
+use std::cmp::Reverse;
+#[cfg(test)]
+use std::collections::{BinaryHeap, HashSet, VecDeque};
+#[cfg(test)]
+use std::ops::Add;
+#[cfg(test)]
+use num::Zero;
+
+pub type NodeId = usize;
+
+// Weighted directed graph. Node labels are interned into dense ids on first
+// use so the traversals can work on plain vectors, every edge is also kept in
+// a reverse adjacency list to answer "who points at this node" cheaply
+#[derive(Clone, Debug)]
+pub struct Graph<N, W> {
+    nodes: Vec<N>,
+    ids: HashMap<N, NodeId>,
+    edges: Vec<Vec<(NodeId, W)>>,
+    reverse: Vec<Vec<(NodeId, W)>>,
+}
+
+impl<N, W> Graph<N, W>
+where N: Clone + Eq + Hash, W: Copy {
+    pub fn new() -> Graph<N, W> {
+        Graph {nodes: Vec::new(), ids: HashMap::new(), edges: Vec::new(), reverse: Vec::new()}
+    }
+
+    // Id of the node, adding it if it hasn't been seen before
+    pub fn add_node(&mut self, node: N) -> NodeId {
+        if let Some(id) = self.ids.get(&node) {
+            return *id;
+        }
+        let id = self.nodes.len();
+        self.nodes.push(node.clone());
+        self.ids.insert(node, id);
+        self.edges.push(Vec::new());
+        self.reverse.push(Vec::new());
+        id
+    }
+
+    pub fn add_edge(&mut self, from: N, to: N, weight: W) -> (NodeId, NodeId) {
+        let from = self.add_node(from);
+        let to = self.add_node(to);
+        self.edges[from].push((to, weight));
+        self.reverse[to].push((from, weight));
+        (from, to)
+    }
+
+    pub fn id(&self, node: &N) -> Option<NodeId> {
+        self.ids.get(node).copied()
+    }
+
+    pub fn node(&self, id: NodeId) -> &N {
+        &self.nodes[id]
+    }
+
+    pub fn len(&self) -> usize {
+        self.nodes.len()
+    }
+
+    #[cfg(test)]
+    pub fn is_empty(&self) -> bool {
+        self.nodes.is_empty()
+    }
+
+    // Outgoing (target, weight) pairs
+    pub fn edges(&self, id: NodeId) -> &[(NodeId, W)] {
+        &self.edges[id]
+    }
+
+    // Incoming (source, weight) pairs
+    #[cfg(test)]
+    pub fn predecessors(&self, id: NodeId) -> &[(NodeId, W)] {
+        &self.reverse[id]
+    }
+
+    // Nodes in the order a breadth first search from start visits them
+    #[cfg(test)]
+    pub fn bfs(&self, start: NodeId) -> Vec<NodeId> {
+        traverse(&self.edges, start, false)
+    }
+
+    // Nodes in the order a depth first search from start visits them
+    #[cfg(test)]
+    pub fn dfs(&self, start: NodeId) -> Vec<NodeId> {
+        traverse(&self.edges, start, true)
+    }
+
+    // Every node reachable from start, start included
+    #[cfg(test)]
+    pub fn reachable_from(&self, start: NodeId) -> HashSet<NodeId> {
+        self.bfs(start).into_iter().collect()
+    }
+
+    // Every node that can reach target, target included
+    #[cfg(test)]
+    pub fn reaching(&self, target: NodeId) -> HashSet<NodeId> {
+        traverse(&self.reverse, target, false).into_iter().collect()
+    }
+
+    // Kahn's algorithm, every node comes before the nodes it points at.
+    // None if the graph has a cycle
+    #[cfg(test)]
+    pub fn topological_sort(&self) -> Option<Vec<NodeId>> {
+        let mut in_degree: Vec<usize> = self.reverse.iter().map(|x| x.len()).collect();
+        let mut queue: VecDeque<NodeId> = (0..self.len()).filter(|x| in_degree[*x] == 0).collect();
+        let mut order: Vec<NodeId> = Vec::with_capacity(self.len());
+
+        while let Some(id) = queue.pop_front() {
+            order.push(id);
+            for (next, _) in &self.edges[id] {
+                in_degree[*next] -= 1;
+                if in_degree[*next] == 0 {
+                    queue.push_back(*next);
+                }
+            }
+        }
+
+        if order.len() == self.len() {
+            return Some(order);
+        }
+        None
+    }
+
+    // Some cycle in the graph as a list of nodes, each pointing at the next and
+    // the last pointing back at the first
+    pub fn find_cycle(&self) -> Option<Vec<NodeId>> {
+        // 0 unvisited, 1 on the current path, 2 finished
+        let mut state: Vec<u8> = vec![0; self.len()];
+        let mut path: Vec<NodeId> = Vec::new();
+
+        for root in 0..self.len() {
+            if state[root] != 0 {
+                continue;
+            }
+            // Stack of (node, index of the next edge to follow)
+            let mut stack: Vec<(NodeId, usize)> = vec![(root, 0)];
+            state[root] = 1;
+            path.push(root);
+
+            while let Some((id, edge)) = stack.pop() {
+                if edge == self.edges[id].len() {
+                    state[id] = 2;
+                    path.pop();
+                    continue;
+                }
+                stack.push((id, edge + 1));
+
+                let next = self.edges[id][edge].0;
+                if state[next] == 1 {
+                    let start = path.iter().position(|x| *x == next).unwrap();
+                    return Some(path[start..].to_vec());
+                }
+                if state[next] == 0 {
+                    state[next] = 1;
+                    path.push(next);
+                    stack.push((next, 0));
+                }
+            }
+        }
+        None
+    }
+}
+
+#[cfg(test)]
+impl<N, W> Graph<N, W>
+where N: Clone + Eq + Hash, W: Copy + Ord + Add<Output = W> + Zero {
+    // Shortest distance from start to every node, None where unreachable.
+    // Weights must not be negative
+    pub fn dijkstra(&self, start: NodeId) -> Vec<Option<W>> {
+        let mut distances: Vec<Option<W>> = vec![None; self.len()];
+        let mut heap: BinaryHeap<Reverse<(W, NodeId)>> = BinaryHeap::new();
+        distances[start] = Some(W::zero());
+        heap.push(Reverse((W::zero(), start)));
+
+        while let Some(Reverse((distance, id))) = heap.pop() {
+            if distances[id].is_some_and(|x| x < distance) {
+                continue;
+            }
+            for (next, weight) in &self.edges[id] {
+                let candidate = distance + *weight;
+                if distances[*next].is_none_or(|x| candidate < x) {
+                    distances[*next] = Some(candidate);
+                    heap.push(Reverse((candidate, *next)));
+                }
+            }
+        }
+        distances
+    }
+
+    // A* from start to goal, returns the cost and the path including both ends.
+    // The heuristic must never overestimate the remaining cost
+    pub fn astar<H>(&self, start: NodeId, goal: NodeId, heuristic: H) -> Option<(W, Vec<NodeId>)>
+    where H: Fn(NodeId) -> W {
+        let mut costs: Vec<Option<W>> = vec![None; self.len()];
+        let mut previous: Vec<Option<NodeId>> = vec![None; self.len()];
+        let mut heap: BinaryHeap<Reverse<(W, W, NodeId)>> = BinaryHeap::new();
+        costs[start] = Some(W::zero());
+        heap.push(Reverse((heuristic(start), W::zero(), start)));
+
+        while let Some(Reverse((_, cost, id))) = heap.pop() {
+            if id == goal {
+                let mut path = vec![goal];
+                while let Some(prior) = previous[*path.last().unwrap()] {
+                    path.push(prior);
+                }
+                path.reverse();
+                return Some((cost, path));
+            }
+            if costs[id].is_some_and(|x| x < cost) {
+                continue;
+            }
+            for (next, weight) in &self.edges[id] {
+                let candidate = cost + *weight;
+                if costs[*next].is_none_or(|x| candidate < x) {
+                    costs[*next] = Some(candidate);
+                    previous[*next] = Some(id);
+                    heap.push(Reverse((candidate + heuristic(*next), candidate, *next)));
+                }
+            }
+        }
+        None
+    }
+
+    // Dijkstra to a single goal, same result shape as astar
+    pub fn shortest_path(&self, start: NodeId, goal: NodeId) -> Option<(W, Vec<NodeId>)> {
+        self.astar(start, goal, |_| W::zero())
+    }
+}
+
+#[cfg(test)]
+// Shared BFS/DFS over an adjacency list, each node is visited once
+fn traverse<W>(adjacency: &[Vec<(NodeId, W)>], start: NodeId, depth_first: bool) -> Vec<NodeId> {
+    let mut seen: Vec<bool> = vec![false; adjacency.len()];
+    let mut order: Vec<NodeId> = Vec::new();
+    let mut queue: VecDeque<NodeId> = VecDeque::new();
+    queue.push_back(start);
+
+    while let Some(id) = if depth_first { queue.pop_back() } else { queue.pop_front() } {
+        if seen[id] {
+            continue;
+        }
+        seen[id] = true;
+        order.push(id);
+
+        // Reversed for DFS so neighbors are explored in insertion order
+        let next = adjacency[id].iter().map(|(x, _)| *x).filter(|x| !seen[*x]);
+        if depth_first {
+            queue.extend(next.collect::<Vec<NodeId>>().into_iter().rev());
+        } else {
+            queue.extend(next);
+        }
+    }
+    order
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn example() -> Graph<&'static str, u32> {
+        let mut graph: Graph<&str, u32> = Graph::new();
+        graph.add_edge("a", "b", 4);
+        graph.add_edge("a", "c", 1);
+        graph.add_edge("c", "b", 2);
+        graph.add_edge("b", "d", 5);
+        graph.add_edge("c", "d", 8);
+        graph.add_node("e");
+        graph
+    }
+
+    #[test]
+    fn interning_test() {
+        let mut graph = example();
+        assert_eq!(5, graph.len());
+        assert!(!graph.is_empty());
+        assert!(Graph::<&str, i32>::new().is_empty());
+        assert_eq!(Some(0), graph.id(&"a"));
+        assert_eq!(None, graph.id(&"z"));
+        assert_eq!(1, graph.add_node("b"));
+        assert_eq!(&"d", graph.node(3));
+        assert_eq!(&[(1, 2), (3, 8)], graph.edges(2));
+        assert_eq!(&[(0, 4), (2, 2)], graph.predecessors(1));
+    }
+
+    #[test]
+    fn traversal_test() {
+        let graph = example();
+        assert_eq!(vec![0, 1, 2, 3], graph.bfs(0));
+        assert_eq!(vec![0, 1, 3, 2], graph.dfs(0));
+        assert_eq!(vec![2, 1, 3], graph.dfs(2));
+
+        let expected: HashSet<NodeId> = vec![0, 1, 2].into_iter().collect();
+        assert_eq!(expected, graph.reaching(1));
+        assert_eq!(3, graph.reachable_from(2).len());
+        assert_eq!(1, graph.reachable_from(4).len());
+    }
+
+    #[test]
+    fn topological_sort_test() {
+        let mut graph = example();
+        let order = graph.topological_sort().unwrap();
+        let position = |id: NodeId| order.iter().position(|x| *x == id).unwrap();
+        for id in 0..graph.len() {
+            for (next, _) in graph.edges(id) {
+                assert!(position(id) < position(*next));
+            }
+        }
+        assert_eq!(None, graph.find_cycle());
+
+        graph.add_edge("d", "c", 1);
+        assert_eq!(None, graph.topological_sort());
+        assert_eq!(Some(vec![1, 3, 2]), graph.find_cycle());
+    }
+
+    #[test]
+    fn shortest_path_test() {
+        let graph = example();
+        assert_eq!(vec![Some(0), Some(3), Some(1), Some(8), None], graph.dijkstra(0));
+        assert_eq!(Some((8, vec![0, 2, 1, 3])), graph.shortest_path(0, 3));
+        assert_eq!(None, graph.shortest_path(3, 0));
+
+        // Grid with unit steps, Manhattan distance is admissible
+        let mut grid: Graph<(i32, i32), u32> = Graph::new();
+        for x in 0..5 {
+            for y in 0..5 {
+                if x < 4 && (x, y) != (1, 1) {
+                    grid.add_edge((x, y), (x + 1, y), 1);
+                    grid.add_edge((x + 1, y), (x, y), 1);
+                }
+                if y < 4 {
+                    grid.add_edge((x, y), (x, y + 1), 1);
+                    grid.add_edge((x, y + 1), (x, y), 1);
+                }
+            }
+        }
+        let start = grid.id(&(0, 0)).unwrap();
+        let goal = grid.id(&(4, 4)).unwrap();
+        let heuristic = |id: NodeId| {
+            let (x, y) = grid.node(id);
+            (4 - x + 4 - y) as u32
+        };
+        let (cost, path) = grid.astar(start, goal, heuristic).unwrap();
+        assert_eq!(8, cost);
+        assert_eq!(9, path.len());
+        assert_eq!(Some(8), grid.dijkstra(start)[goal]);
+    }
+}
@@ -0,0 +1,363 @@
+use std::fmt;
+use std::ops::{Index, IndexMut};
+
+use crate::common::convertor::ParseError;
+
+// (dx, dy) offsets with y growing downwards, clockwise starting north
+#[cfg(test)]
+pub const NEIGHBORS4: [(i64, i64); 4] = [(0, -1), (1, 0), (0, 1), (-1, 0)];
+pub const NEIGHBORS8: [(i64, i64); 8] = [(0, -1), (1, -1), (1, 0), (1, 1), (0, 1), (-1, 1), (-1, 0), (-1, -1)];
+
+// Row-major 2D grid, (x, y) is column x of row y with (0, 0) at the top left
+#[derive(Clone, PartialEq, Eq, Hash, Debug)]
+pub struct Grid<T> {
+    width: usize,
+    height: usize,
+    cells: Vec<T>,
+}
+
+impl<T> Grid<T> {
+    pub fn from_vec(width: usize, height: usize, cells: Vec<T>) -> Grid<T> {
+        assert_eq!(width * height, cells.len(), "Grid of {}x{} needs {} cells", width, height, width * height);
+        Grid {width, height, cells}
+    }
+
+    // One row per line, every line must be as wide as the first
+    pub fn parse_with<S, F>(lines: &[S], convert: F) -> Result<Grid<T>, ParseError>
+    where S: AsRef<str>, F: Fn(char) -> T {
+        let width = lines.first().map(|x| x.as_ref().chars().count()).unwrap_or(0);
+        let mut cells: Vec<T> = Vec::with_capacity(width * lines.len());
+
+        for (index, line) in lines.iter().enumerate() {
+            let line = line.as_ref();
+            let before = cells.len();
+            cells.extend(line.chars().map(&convert));
+            if cells.len() - before != width {
+                return Err(ParseError::new(index + 1, None, line, format!("expected {} cells", width)));
+            }
+        }
+
+        Ok(Grid::from_vec(width, lines.len(), cells))
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn in_bounds(&self, x: i64, y: i64) -> bool {
+        x >= 0 && y >= 0 && (x as usize) < self.width && (y as usize) < self.height
+    }
+
+    pub fn get(&self, x: i64, y: i64) -> Option<&T> {
+        if !self.in_bounds(x, y) {
+            return None;
+        }
+        Some(&self.cells[y as usize * self.width + x as usize])
+    }
+
+    #[cfg(test)]
+    pub fn get_mut(&mut self, x: i64, y: i64) -> Option<&mut T> {
+        if !self.in_bounds(x, y) {
+            return None;
+        }
+        Some(&mut self.cells[y as usize * self.width + x as usize])
+    }
+
+    // Toroidal access, coordinates outside the grid wrap around on both axes
+    pub fn get_wrapping(&self, x: i64, y: i64) -> &T {
+        let x = x.rem_euclid(self.width as i64) as usize;
+        let y = y.rem_euclid(self.height as i64) as usize;
+        &self.cells[y * self.width + x]
+    }
+
+    #[cfg(test)]
+    pub fn row(&self, y: usize) -> &[T] {
+        &self.cells[y * self.width..(y + 1) * self.width]
+    }
+
+    pub fn rows(&self) -> impl Iterator<Item = &[T]> {
+        // chunks panics on 0, an empty grid has no rows either way
+        self.cells.chunks(self.width.max(1))
+    }
+
+    #[cfg(test)]
+    pub fn column(&self, x: usize) -> impl Iterator<Item = &T> {
+        self.cells.iter().skip(x).step_by(self.width.max(1)).take(self.height)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &T> {
+        self.cells.iter()
+    }
+
+    // Cells with their (x, y) position, row by row
+    pub fn positions(&self) -> impl Iterator<Item = ((usize, usize), &T)> {
+        let width = self.width;
+        self.cells.iter().enumerate().map(move |(i, cell)| ((i % width, i / width), cell))
+    }
+
+    fn neighbors<'a>(&self, x: usize, y: usize, offsets: &'a [(i64, i64)]) -> impl Iterator<Item = (usize, usize)> + 'a {
+        let (width, height) = (self.width as i64, self.height as i64);
+        offsets
+            .iter()
+            .map(move |(dx, dy)| (x as i64 + dx, y as i64 + dy))
+            .filter(move |(i, j)| *i >= 0 && *j >= 0 && *i < width && *j < height)
+            .map(|(i, j)| (i as usize, j as usize))
+    }
+
+    // In-bounds orthogonal neighbors of (x, y)
+    #[cfg(test)]
+    pub fn neighbors4(&self, x: usize, y: usize) -> impl Iterator<Item = (usize, usize)> {
+        self.neighbors(x, y, &NEIGHBORS4)
+    }
+
+    // In-bounds orthogonal and diagonal neighbors of (x, y)
+    pub fn neighbors8(&self, x: usize, y: usize) -> impl Iterator<Item = (usize, usize)> {
+        self.neighbors(x, y, &NEIGHBORS8)
+    }
+
+    // Walk from (x, y) in steps of (dx, dy) and return the first cell matching
+    // predicate, the start cell itself is not considered
+    #[cfg(test)]
+    pub fn first_visible<P>(&self, x: usize, y: usize, (dx, dy): (i64, i64), predicate: P) -> Option<(usize, usize)>
+    where P: Fn(&T) -> bool {
+        assert!((dx, dy) != (0, 0), "first_visible needs a non zero direction");
+        let mut i = x as i64 + dx;
+        let mut j = y as i64 + dy;
+
+        while let Some(cell) = self.get(i, j) {
+            if predicate(cell) {
+                return Some((i as usize, j as usize));
+            }
+            i += dx;
+            j += dy;
+        }
+
+        None
+    }
+
+    // For every cell, the first matching cell along each of the given directions,
+    // in the same order with None where the ray leaves the grid. A cell sees its
+    // neighbor if that matches, else whatever the neighbor sees, so visiting the
+    // neighbor first makes this one pass over the grid per direction
+    pub fn first_visible_all<P>(&self, directions: &[(i64, i64)], predicate: P) -> Grid<Vec<Option<(usize, usize)>>>
+    where P: Fn(&T) -> bool {
+        assert!(!directions.contains(&(0, 0)), "first_visible_all needs non zero directions");
+        let mut result: Grid<Vec<Option<(usize, usize)>>> = Grid::new(self.width, self.height, Vec::with_capacity(directions.len()));
+
+        for (dx, dy) in directions.iter() {
+            let xs: Vec<usize> = if *dx > 0 { (0..self.width).rev().collect() } else { (0..self.width).collect() };
+            let ys: Vec<usize> = if *dy > 0 { (0..self.height).rev().collect() } else { (0..self.height).collect() };
+
+            for y in ys.iter() {
+                for x in xs.iter() {
+                    let (i, j) = (*x as i64 + dx, *y as i64 + dy);
+                    let seen = match self.get(i, j) {
+                        Some(cell) if predicate(cell) => Some((i as usize, j as usize)),
+                        Some(_) => *result[(i as usize, j as usize)].last().unwrap(),
+                        None => None,
+                    };
+                    result[(*x, *y)].push(seen);
+                }
+            }
+        }
+
+        result
+    }
+
+    pub fn map<U, F>(&self, convert: F) -> Grid<U>
+    where F: Fn(&T) -> U {
+        Grid::from_vec(self.width, self.height, self.cells.iter().map(convert).collect())
+    }
+
+    // New grid of the given size where each cell is taken from this grid
+    #[cfg(test)]
+    fn remap(&self, width: usize, height: usize, source: impl Fn(usize, usize) -> (usize, usize)) -> Grid<T>
+    where T: Clone {
+        let mut cells: Vec<T> = Vec::with_capacity(self.cells.len());
+        for y in 0..height {
+            for x in 0..width {
+                cells.push(self[source(x, y)].clone());
+            }
+        }
+        Grid::from_vec(width, height, cells)
+    }
+}
+
+impl<T: Clone> Grid<T> {
+    pub fn new(width: usize, height: usize, fill: T) -> Grid<T> {
+        Grid::from_vec(width, height, vec![fill; width * height])
+    }
+
+    #[cfg(test)]
+    pub fn transpose(&self) -> Grid<T> {
+        self.remap(self.height, self.width, |x, y| (y, x))
+    }
+
+    #[cfg(test)]
+    pub fn rotate_clockwise(&self) -> Grid<T> {
+        let height = self.height;
+        self.remap(self.height, self.width, |x, y| (y, height - 1 - x))
+    }
+
+    #[cfg(test)]
+    pub fn rotate_counter_clockwise(&self) -> Grid<T> {
+        let width = self.width;
+        self.remap(self.height, self.width, |x, y| (width - 1 - y, x))
+    }
+
+    // Mirror left to right
+    #[cfg(test)]
+    pub fn flip_horizontal(&self) -> Grid<T> {
+        let width = self.width;
+        self.remap(self.width, self.height, |x, y| (width - 1 - x, y))
+    }
+
+    // Mirror top to bottom
+    #[cfg(test)]
+    pub fn flip_vertical(&self) -> Grid<T> {
+        let height = self.height;
+        self.remap(self.width, self.height, |x, y| (x, height - 1 - y))
+    }
+}
+
+impl Grid<char> {
+    pub fn parse<S: AsRef<str>>(lines: &[S]) -> Result<Grid<char>, ParseError> {
+        Grid::parse_with(lines, |x| x)
+    }
+}
+
+impl<T> Index<(usize, usize)> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, (x, y): (usize, usize)) -> &T {
+        assert!(x < self.width && y < self.height, "({}, {}) is outside the {}x{} grid", x, y, self.width, self.height);
+        &self.cells[y * self.width + x]
+    }
+}
+
+impl<T> IndexMut<(usize, usize)> for Grid<T> {
+    fn index_mut(&mut self, (x, y): (usize, usize)) -> &mut T {
+        assert!(x < self.width && y < self.height, "({}, {}) is outside the {}x{} grid", x, y, self.width, self.height);
+        &mut self.cells[y * self.width + x]
+    }
+}
+
+// One line per row, cells printed back to back, so a char map prints as it was parsed
+impl<T: fmt::Display> fmt::Display for Grid<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for (y, row) in self.rows().enumerate() {
+            if y > 0 {
+                writeln!(f)?;
+            }
+            for cell in row {
+                write!(f, "{}", cell)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn example() -> Grid<char> {
+        Grid::parse(&["abc", "def"]).unwrap()
+    }
+
+    #[test]
+    fn parse_test() {
+        let grid = example();
+        assert_eq!(3, grid.width());
+        assert_eq!(2, grid.height());
+        assert_eq!('f', grid[(2, 1)]);
+        assert_eq!("abc\ndef", grid.to_string());
+
+        let error = Grid::parse(&["abc", "de"]).unwrap_err();
+        assert_eq!(2, error.line);
+
+        let digits = Grid::parse_with(&["12", "34"], |x| x.to_digit(10).unwrap()).unwrap();
+        assert_eq!(10, digits.iter().sum::<u32>());
+    }
+
+    #[test]
+    fn access_test() {
+        let mut grid = example();
+        assert_eq!(Some(&'a'), grid.get(0, 0));
+        assert_eq!(None, grid.get(-1, 0));
+        assert_eq!(None, grid.get(3, 0));
+        assert_eq!(None, grid.get(0, 2));
+        assert_eq!(&'a', grid.get_wrapping(3, 2));
+        assert_eq!(&'f', grid.get_wrapping(-1, -1));
+
+        *grid.get_mut(1, 1).unwrap() = 'x';
+        grid[(0, 1)] = 'y';
+        assert_eq!("abc\nyxf", grid.to_string());
+    }
+
+    #[test]
+    fn iterators_test() {
+        let grid = example();
+        assert_eq!(&['d', 'e', 'f'], grid.row(1));
+        assert_eq!(2, grid.rows().count());
+        assert_eq!(vec![&'b', &'e'], grid.column(1).collect::<Vec<&char>>());
+        assert_eq!(Some(((1, 1), &'e')), grid.positions().nth(4));
+
+        let mut neighbors: Vec<(usize, usize)> = grid.neighbors4(0, 0).collect();
+        neighbors.sort();
+        assert_eq!(vec![(0, 1), (1, 0)], neighbors);
+        assert_eq!(5, grid.neighbors8(1, 0).count());
+        assert_eq!(3, grid.neighbors4(1, 1).count());
+    }
+
+    #[test]
+    fn first_visible_test() {
+        let grid = Grid::parse(&[
+            "#..#.",
+            ".....",
+            "..#..",
+            "#...#"]).unwrap();
+        let wall = |x: &char| *x == '#';
+        assert_eq!(Some((3, 0)), grid.first_visible(0, 0, (1, 0), wall));
+        assert_eq!(Some((2, 2)), grid.first_visible(0, 0, (1, 1), wall));
+        assert_eq!(Some((0, 3)), grid.first_visible(0, 0, (0, 1), wall));
+        assert_eq!(None, grid.first_visible(0, 0, (-1, 0), wall));
+        assert_eq!(None, grid.first_visible(4, 0, (0, 1), |x| *x == 'x'));
+
+        let all = grid.first_visible_all(&NEIGHBORS8, wall);
+        for ((x, y), seen) in all.positions() {
+            assert_eq!(8, seen.len());
+            for (index, direction) in NEIGHBORS8.iter().enumerate() {
+                assert_eq!(grid.first_visible(x, y, *direction, wall), seen[index]);
+            }
+        }
+    }
+
+    #[test]
+    #[should_panic]
+    fn first_visible_zero_direction_test() {
+        Grid::parse(&["#."]).unwrap().first_visible(1, 0, (0, 0), |x| *x == '#');
+    }
+
+    #[test]
+    #[should_panic]
+    fn first_visible_all_zero_direction_test() {
+        Grid::parse(&["#."]).unwrap().first_visible_all(&[(1, 0), (0, 0)], |x| *x == '#');
+    }
+
+    #[test]
+    fn transform_test() {
+        let grid = example();
+        assert_eq!("ad\nbe\ncf", grid.transpose().to_string());
+        assert_eq!("da\neb\nfc", grid.rotate_clockwise().to_string());
+        assert_eq!("cf\nbe\nad", grid.rotate_counter_clockwise().to_string());
+        assert_eq!("cba\nfed", grid.flip_horizontal().to_string());
+        assert_eq!("def\nabc", grid.flip_vertical().to_string());
+        assert_eq!(grid, grid.rotate_clockwise().rotate_clockwise().rotate_clockwise().rotate_clockwise());
+        assert_eq!(Grid::new(2, 3, 0), grid.map(|_| 0).transpose());
+    }
+}
@@ -0,0 +1,122 @@
+// Axial coordinates for a pointy-topped hex grid, the implicit third cube
+// coordinate is s = -q - r. Rows run east-west, so moving north-east or
+// south-west also changes q, see https://www.redblobgames.com/grids/hexagons/
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug, Default)]
+pub struct Hex {
+    pub q: i32,
+    pub r: i32
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum HexDir {
+    East,
+    SouthEast,
+    SouthWest,
+    West,
+    NorthWest,
+    NorthEast
+}
+
+pub const HEX_DIRS: [HexDir; 6] = [
+    HexDir::East,
+    HexDir::SouthEast,
+    HexDir::SouthWest,
+    HexDir::West,
+    HexDir::NorthWest,
+    HexDir::NorthEast
+];
+
+impl HexDir {
+    pub fn offset(&self) -> Hex {
+        match self {
+            HexDir::East => Hex::new(1, 0),
+            HexDir::SouthEast => Hex::new(0, 1),
+            HexDir::SouthWest => Hex::new(-1, 1),
+            HexDir::West => Hex::new(-1, 0),
+            HexDir::NorthWest => Hex::new(0, -1),
+            HexDir::NorthEast => Hex::new(1, -1)
+        }
+    }
+}
+
+impl Hex {
+    pub fn new(q: i32, r: i32) -> Hex {
+        Hex {q, r}
+    }
+
+    pub fn step(&self, dir: HexDir) -> Hex {
+        let offset = dir.offset();
+        Hex::new(self.q + offset.q, self.r + offset.r)
+    }
+
+    pub fn neighbors(&self) -> [Hex; 6] {
+        let mut neighbors = [*self; 6];
+        for i in 0..HEX_DIRS.len() {
+            neighbors[i] = self.step(HEX_DIRS[i]);
+        }
+        neighbors
+    }
+}
+
+// Parse an undelimited path of e, se, sw, w, nw and ne steps
+pub fn parse_path(path: &str) -> Result<Vec<HexDir>, String> {
+    let mut dirs: Vec<HexDir> = Vec::new();
+    let mut chars = path.trim().chars();
+
+    while let Some(character) = chars.next() {
+        let dir = match character {
+            'e' => HexDir::East,
+            'w' => HexDir::West,
+            'n' | 's' => match (character, chars.next()) {
+                ('n', Some('e')) => HexDir::NorthEast,
+                ('n', Some('w')) => HexDir::NorthWest,
+                ('s', Some('e')) => HexDir::SouthEast,
+                ('s', Some('w')) => HexDir::SouthWest,
+                _ => return Err(format!("Incomplete direction after '{}' in {}", character, path))
+            },
+            _ => return Err(format!("Unknown direction '{}' in {}", character, path))
+        };
+        dirs.push(dir);
+    }
+
+    Ok(dirs)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_path_test() {
+        assert_eq!(vec![HexDir::East, HexDir::SouthEast, HexDir::NorthEast], parse_path("esene").unwrap());
+        assert!(parse_path("ex").is_err());
+        assert!(parse_path("en").is_err());
+    }
+
+    #[test]
+    fn step_test() {
+        let origin = Hex::default();
+        let mut position = origin;
+        for dir in parse_path("nwwswee").unwrap() {
+            position = position.step(dir);
+        }
+        assert_eq!(origin, position);
+
+        let mut position = origin;
+        for dir in parse_path("esew").unwrap() {
+            position = position.step(dir);
+        }
+        assert_eq!(origin.step(HexDir::SouthEast), position);
+    }
+
+    #[test]
+    fn neighbors_test() {
+        let origin = Hex::default();
+        let neighbors = origin.neighbors();
+        assert_eq!(Hex::new(1, 0), neighbors[0]);
+        assert_eq!(Hex::new(1, -1), neighbors[5]);
+        for neighbor in neighbors.iter() {
+            assert!(neighbor.neighbors().contains(&origin));
+        }
+    }
+}
@@ -0,0 +1,3 @@
+// Puzzle inputs compiled into the binary, generated by build.rs as
+// `embedded("dayN") -> Option<&'static str>`
+include!(concat!(env!("OUT_DIR"), "/inputs.rs"));
@@ -0,0 +1,280 @@
+// Inclusive integer ranges and sets of them, written as "a-b" and "a-b or c-d"
+
+use std::fmt;
+use std::str::FromStr;
+use num::PrimInt;
+
+#[cfg(test)]
+use crate::common::search;
+
+// start..=end, empty when start > end
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub struct Interval<T> {
+    pub start: T,
+    pub end: T
+}
+
+impl<T: PrimInt> Interval<T> {
+    pub fn new(start: T, end: T) -> Interval<T> {
+        Interval {start, end}
+    }
+
+    #[cfg(test)]
+    pub fn is_empty(&self) -> bool {
+        self.start > self.end
+    }
+
+    // Number of values covered, None if that doesn't fit in T like for all of u8
+    #[cfg(test)]
+    pub fn len(&self) -> Option<T> {
+        if self.is_empty() {
+            return Some(T::zero());
+        }
+        self.end.checked_sub(&self.start)?.checked_add(&T::one())
+    }
+
+    pub fn contains(&self, value: T) -> bool {
+        self.start <= value && value <= self.end
+    }
+
+    #[cfg(test)]
+    pub fn contains_interval(&self, other: &Interval<T>) -> bool {
+        other.is_empty() || (self.start <= other.start && other.end <= self.end)
+    }
+
+    #[cfg(test)]
+    pub fn intersection(&self, other: &Interval<T>) -> Option<Interval<T>> {
+        let overlap = Interval::new(self.start.max(other.start), self.end.min(other.end));
+        if overlap.is_empty() {
+            return None;
+        }
+        Some(overlap)
+    }
+
+    // Overlapping or directly next to each other, like 1-3 and 4-6
+    #[cfg(test)]
+    pub fn touches(&self, other: &Interval<T>) -> bool {
+        if self.is_empty() || other.is_empty() {
+            return false;
+        }
+        let (first, second) = if self.start <= other.start { (self, other) } else { (other, self) };
+        second.start <= first.end || first.end.checked_add(&T::one()) == Some(second.start)
+    }
+
+    // Single interval covering both, None if there would be a gap
+    #[cfg(test)]
+    pub fn union(&self, other: &Interval<T>) -> Option<Interval<T>> {
+        if other.is_empty() {
+            return Some(*self);
+        }
+        if self.is_empty() {
+            return Some(*other);
+        }
+        if !self.touches(other) {
+            return None;
+        }
+        Some(Interval::new(self.start.min(other.start), self.end.max(other.end)))
+    }
+}
+
+impl<T: PrimInt + FromStr> FromStr for Interval<T> {
+    type Err = String;
+
+    // "a-b", the separator is the first '-' after the start so "-5--1" works too
+    fn from_str(text: &str) -> Result<Interval<T>, String> {
+        let text = text.trim();
+        let separator = text
+            .char_indices()
+            .skip(1)
+            .find(|(_, c)| *c == '-')
+            .map(|(i, _)| i)
+            .ok_or(format!("Missing '-' in interval {}", text))?;
+
+        let parse = |bound: &str| bound.trim().parse::<T>().map_err(|_| format!("Bad bound '{}' in interval {}", bound, text));
+        Ok(Interval::new(parse(&text[..separator])?, parse(&text[separator + 1..])?))
+    }
+}
+
+impl<T: fmt::Display> fmt::Display for Interval<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}-{}", self.start, self.end)
+    }
+}
+
+// Union of intervals, kept sorted with everything that touches merged so a
+// value lookup is a binary search
+#[cfg(test)]
+#[derive(Clone, PartialEq, Eq, Hash, Debug, Default)]
+pub struct IntervalSet<T> {
+    intervals: Vec<Interval<T>>
+}
+
+#[cfg(test)]
+impl<T: PrimInt> IntervalSet<T> {
+    pub fn new() -> IntervalSet<T> {
+        IntervalSet {intervals: Vec::new()}
+    }
+
+    pub fn from_intervals(intervals: Vec<Interval<T>>) -> IntervalSet<T> {
+        IntervalSet {intervals: merge(intervals)}
+    }
+
+    // Disjoint, non adjacent and in ascending order
+    pub fn intervals(&self) -> &[Interval<T>] {
+        &self.intervals
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.intervals.is_empty()
+    }
+
+    // Number of values covered, None if that doesn't fit in T
+    pub fn len(&self) -> Option<T> {
+        self.intervals.iter().try_fold(T::zero(), |total, x| total.checked_add(&x.len()?))
+    }
+
+    pub fn insert(&mut self, interval: Interval<T>) {
+        let mut intervals = std::mem::take(&mut self.intervals);
+        intervals.push(interval);
+        self.intervals = merge(intervals);
+    }
+
+    pub fn contains(&self, value: T) -> bool {
+        // Last interval starting at or before value is the only candidate
+        let index = search::partition_point(&self.intervals, |x| x.start <= value);
+        index > 0 && self.intervals[index - 1].contains(value)
+    }
+
+    pub fn union(&self, other: &IntervalSet<T>) -> IntervalSet<T> {
+        let intervals = self.intervals.iter().chain(other.intervals.iter()).cloned().collect();
+        IntervalSet::from_intervals(intervals)
+    }
+
+    // Walk both sorted lists at once, always stepping past whichever interval ends first
+    pub fn intersection(&self, other: &IntervalSet<T>) -> IntervalSet<T> {
+        let mut intervals: Vec<Interval<T>> = Vec::new();
+        let (mut i, mut j) = (0, 0);
+        while i < self.intervals.len() && j < other.intervals.len() {
+            let (a, b) = (self.intervals[i], other.intervals[j]);
+            if let Some(overlap) = a.intersection(&b) {
+                intervals.push(overlap);
+            }
+            if a.end < b.end {
+                i += 1;
+            } else {
+                j += 1;
+            }
+        }
+        IntervalSet {intervals}
+    }
+}
+
+// Sort and fold together everything that overlaps or touches, dropping empties
+#[cfg(test)]
+pub fn merge<T: PrimInt>(intervals: Vec<Interval<T>>) -> Vec<Interval<T>> {
+    let mut sorted: Vec<Interval<T>> = intervals.into_iter().filter(|x| !x.is_empty()).collect();
+    sorted.sort_by_key(|x| x.start);
+
+    let mut merged: Vec<Interval<T>> = Vec::new();
+    for interval in sorted {
+        match merged.last_mut() {
+            Some(last) if last.touches(&interval) => *last = last.union(&interval).unwrap(),
+            _ => merged.push(interval)
+        }
+    }
+    merged
+}
+
+#[cfg(test)]
+impl<T: PrimInt + FromStr> FromStr for IntervalSet<T> {
+    type Err = String;
+
+    // "a-b or c-d or ..."
+    fn from_str(text: &str) -> Result<IntervalSet<T>, String> {
+        let intervals = text
+            .split(" or ")
+            .map(|x| x.parse::<Interval<T>>())
+            .collect::<Result<Vec<Interval<T>>, String>>()?;
+        Ok(IntervalSet::from_intervals(intervals))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn interval_test() {
+        let a: Interval<i32> = Interval::new(1, 3);
+        let b = Interval::new(4, 6);
+        let c = Interval::new(2, 8);
+        assert_eq!(Some(3), a.len());
+        assert!(a.contains(1) && a.contains(3) && !a.contains(4));
+        assert!(c.contains_interval(&b));
+        assert!(!b.contains_interval(&c));
+        assert_eq!(Some(Interval::new(2, 3)), a.intersection(&c));
+        assert_eq!(None, a.intersection(&b));
+        assert!(a.touches(&b));
+        assert_eq!(Some(Interval::new(1, 6)), b.union(&a));
+        assert_eq!(None, a.union(&Interval::new(5, 6)));
+
+        let empty = Interval::new(5, 4);
+        assert!(empty.is_empty());
+        assert_eq!(Some(0), empty.len());
+        assert_eq!(Some(a), a.union(&empty));
+        assert_eq!("1-3", a.to_string());
+    }
+
+    #[test]
+    fn interval_extremes_test() {
+        let all: Interval<u8> = Interval::new(0, 255);
+        assert_eq!(None, all.len());
+        assert_eq!(Some(255), Interval::new(1u8, 255).len());
+        assert_eq!(None, Interval::new(i8::MIN, i8::MAX).len());
+        assert_eq!(None, Interval::new(i8::MIN, -1).len());
+        assert_eq!(Some(127), Interval::new(-127i8, -1).len());
+
+        assert!(Interval::new(0u8, 254).touches(&Interval::new(255, 255)));
+        assert!(!Interval::new(i8::MIN, -100).touches(&Interval::new(100, i8::MAX)));
+        assert!(Interval::new(250u8, 255).touches(&Interval::new(255, 255)));
+        assert_eq!(Some(all), Interval::new(0u8, 100).union(&Interval::new(101, 255)));
+
+        let set: IntervalSet<u8> = "0-200 or 201-255".parse().unwrap();
+        assert_eq!(&[all], set.intervals());
+        assert_eq!(None, set.len());
+        assert_eq!(Some(200), "0-99 or 156-255".parse::<IntervalSet<u8>>().unwrap().len());
+    }
+
+    #[test]
+    fn interval_parse_test() {
+        assert_eq!(Ok(Interval::new(1, 3)), "1-3".parse::<Interval<i8>>());
+        assert_eq!(Ok(Interval::new(-5, -1)), " -5--1 ".parse::<Interval<i64>>());
+        assert!("13".parse::<Interval<i32>>().is_err());
+        assert!("a-3".parse::<Interval<i32>>().is_err());
+        assert!("1-300".parse::<Interval<u8>>().is_err());
+    }
+
+    #[test]
+    fn interval_set_test() {
+        let set: IntervalSet<u32> = "1-3 or 5-7".parse().unwrap();
+        assert_eq!(&[Interval::new(1, 3), Interval::new(5, 7)], set.intervals());
+        assert_eq!(Some(6), set.len());
+        let found: Vec<u32> = (0..10).filter(|x| set.contains(*x)).collect();
+        assert_eq!(vec![1, 2, 3, 5, 6, 7], found);
+
+        let merged: IntervalSet<u32> = "6-11 or 33-44 or 1-5 or 13-40".parse().unwrap();
+        assert_eq!(&[Interval::new(1, 11), Interval::new(13, 44)], merged.intervals());
+
+        let other: IntervalSet<u32> = "3-5 or 7-20".parse().unwrap();
+        assert_eq!("3-3 or 5-5 or 7-7".parse::<IntervalSet<u32>>().unwrap(), set.intersection(&other));
+        assert_eq!("1-20".parse::<IntervalSet<u32>>().unwrap(), set.union(&other));
+        assert!(set.intersection(&IntervalSet::new()).is_empty());
+
+        let mut growing = IntervalSet::new();
+        growing.insert(Interval::new(10, 12));
+        growing.insert(Interval::new(1, 2));
+        growing.insert(Interval::new(3, 9));
+        assert_eq!(&[Interval::new(1, 12)], growing.intervals());
+        assert!("1-3 or x".parse::<IntervalSet<u32>>().is_err());
+    }
+}
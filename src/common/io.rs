@@ -1,13 +1,143 @@
 use std::{
     vec::Vec,
+    fmt,
+    fs,
     fs::File,
     io::{prelude::*, BufReader},
-    path::Path,
+    path::{Path, PathBuf},
 };
 
+// Where a day's puzzle input comes from
+#[derive(Clone, PartialEq, Debug)]
+pub enum InputSource {
+    File(PathBuf),
+    // Contents compiled into the binary, see common::inputs
+    #[cfg(feature = "embed-inputs")]
+    Embedded(&'static str),
+}
+
+impl InputSource {
+    pub fn lines(&self) -> Vec<String> {
+        match self {
+            InputSource::File(path) => lines_from_file(path),
+            #[cfg(feature = "embed-inputs")]
+            InputSource::Embedded(contents) => contents.lines().map(|l| l.to_string()).collect(),
+        }
+    }
+}
+
+impl fmt::Display for InputSource {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            InputSource::File(path) => write!(f, "{}", path.display()),
+            #[cfg(feature = "embed-inputs")]
+            InputSource::Embedded(_) => write!(f, "the embedded input"),
+        }
+    }
+}
+
 pub fn lines_from_file(filename: impl AsRef<Path>) -> Vec<String> {
     let file = File::open(filename).expect("no such file");
     let buf = BufReader::new(file);
-    
-    return buf.lines().map(|l| l.expect("Could not parse line")).collect();
-}
\ No newline at end of file
+
+    buf.lines().map(|l| l.expect("Could not parse line")).collect()
+}
+
+// Files at least this large are memory-mapped when the mmap feature is on
+#[cfg(feature = "mmap")]
+const MMAP_THRESHOLD: u64 = 1 << 20;
+
+enum Storage {
+    Owned(String),
+    #[cfg(feature = "embed-inputs")]
+    Embedded(&'static str),
+    #[cfg(feature = "mmap")]
+    Mapped(memmap2::Mmap),
+}
+
+// Whole input read once, lines and bytes are handed out as borrowed slices
+// so parsers can work on &str without a String per line
+pub struct Input {
+    storage: Storage,
+}
+
+impl Input {
+    pub fn from_source(source: &InputSource) -> Input {
+        match source {
+            InputSource::File(path) => Input::from_file(path),
+            #[cfg(feature = "embed-inputs")]
+            InputSource::Embedded(contents) => Input {storage: Storage::Embedded(contents)},
+        }
+    }
+
+    pub fn from_file(filename: impl AsRef<Path>) -> Input {
+        let filename = filename.as_ref();
+
+        #[cfg(feature = "mmap")]
+        {
+            let file = File::open(filename).expect("no such file");
+            if file.metadata().expect("Could not stat file").len() >= MMAP_THRESHOLD {
+                // Safety: inputs are not modified while the puzzle runs
+                let map = unsafe { memmap2::Mmap::map(&file) }.expect("Could not map file");
+                std::str::from_utf8(&map).expect("Input is not valid UTF-8");
+                return Input {storage: Storage::Mapped(map)};
+            }
+        }
+
+        let contents = fs::read_to_string(filename).expect("no such file");
+        Input::from_string(contents)
+    }
+
+    pub fn from_string(contents: String) -> Input {
+        Input {storage: Storage::Owned(contents)}
+    }
+
+    pub fn as_str(&self) -> &str {
+        match &self.storage {
+            Storage::Owned(contents) => contents,
+            #[cfg(feature = "embed-inputs")]
+            Storage::Embedded(contents) => contents,
+            // Checked to be UTF-8 when the file was mapped
+            #[cfg(feature = "mmap")]
+            Storage::Mapped(map) => unsafe { std::str::from_utf8_unchecked(map) },
+        }
+    }
+
+    // Lines without their "\n" or "\r\n" terminator
+    pub fn lines(&self) -> std::str::Lines<'_> {
+        self.as_str().lines()
+    }
+
+    pub fn line_vec(&self) -> Vec<&str> {
+        self.lines().collect()
+    }
+
+    // Lines as raw bytes, for ASCII maps indexed by position
+    #[cfg(test)]
+    pub fn byte_lines(&self) -> impl Iterator<Item = &[u8]> {
+        self.lines().map(|x| x.as_bytes())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn input_lines_test() {
+        let input = Input::from_string("abc\r\n\ndef\n".to_string());
+        assert_eq!(vec!["abc", "", "def"], input.line_vec());
+        assert_eq!(vec![&b"abc"[..], &b""[..], &b"def"[..]], input.byte_lines().collect::<Vec<&[u8]>>());
+    }
+
+    #[test]
+    fn input_from_file_test() {
+        let input = Input::from_file("src/inputs/day15");
+        let lines = lines_from_file("src/inputs/day15");
+        assert_eq!(lines, input.line_vec());
+
+        let source = InputSource::File(PathBuf::from("src/inputs/day15"));
+        assert_eq!(lines, source.lines());
+        assert_eq!(lines, Input::from_source(&source).line_vec());
+    }
+}
@@ -0,0 +1,245 @@
+// Finding k distinct elements of a list that add up to a target. Solutions are
+// given as ascending index lists into the original slice, pick the values out
+// with values(). Both variants are O(n^(k - 1)) for k >= 2
+
+use std::cmp::Ordering;
+#[cfg(test)]
+use std::collections::HashMap;
+#[cfg(test)]
+use std::hash::Hash;
+use num::PrimInt;
+
+// First solution found by sorting and closing in with two pointers
+pub fn first<T>(numbers: &[T], k: usize, target: T) -> Option<Vec<usize>>
+where T: PrimInt {
+    let mut results: Vec<Vec<usize>> = Vec::new();
+    sorted_search(numbers, &sorted_order(numbers), k, target, 0, &mut Vec::new(), &mut results, true);
+    results.pop()
+}
+
+// Every solution, one per distinct combination of values. Indices of repeated
+// values are not permuted, so [1, 1, 1] with k = 2 and target 2 gives one answer
+#[cfg(test)]
+pub fn all<T>(numbers: &[T], k: usize, target: T) -> Vec<Vec<usize>>
+where T: PrimInt {
+    let mut results: Vec<Vec<usize>> = Vec::new();
+    sorted_search(numbers, &sorted_order(numbers), k, target, 0, &mut Vec::new(), &mut results, false);
+    results
+}
+
+// First solution using a hash lookup for the last element of the pair instead
+// of sorting, better when the slice is small or changes between calls
+#[cfg(test)]
+pub fn first_hashed<T>(numbers: &[T], k: usize, target: T) -> Option<Vec<usize>>
+where T: PrimInt + Hash {
+    hashed_search(numbers, k, target, 0, &mut Vec::new())
+}
+
+pub fn values<T>(numbers: &[T], indices: &[usize]) -> Vec<T>
+where T: Copy {
+    indices.iter().map(|x| numbers[*x]).collect()
+}
+
+// Indices of numbers ordered by value
+fn sorted_order<T>(numbers: &[T]) -> Vec<usize>
+where T: PrimInt {
+    let mut order: Vec<usize> = (0..numbers.len()).collect();
+    order.sort_by_key(|x| numbers[*x]);
+    order
+}
+
+fn found(chosen: &[usize], rest: &[usize]) -> Vec<usize> {
+    let mut solution: Vec<usize> = chosen.iter().chain(rest.iter()).cloned().collect();
+    solution.sort();
+    solution
+}
+
+// Fix the smallest remaining element and recurse until two are left, those
+// are found with a pointer from each end of the sorted order
+#[allow(clippy::too_many_arguments)]
+fn sorted_search<T>(
+    numbers: &[T],
+    order: &[usize],
+    k: usize,
+    target: T,
+    from: usize,
+    chosen: &mut Vec<usize>,
+    results: &mut Vec<Vec<usize>>,
+    first_only: bool)
+where T: PrimInt {
+    let value = |i: usize| numbers[order[i]];
+
+    if k == 0 {
+        if target == T::zero() {
+            results.push(found(chosen, &[]));
+        }
+        return;
+    }
+
+    if k == 1 {
+        if let Some(i) = (from..order.len()).find(|i| value(*i) == target) {
+            results.push(found(chosen, &[order[i]]));
+        }
+        return;
+    }
+
+    if k == 2 {
+        if order.len() < from + 2 {
+            return;
+        }
+        let mut low = from;
+        let mut high = order.len() - 1;
+        while low < high {
+            // A sum that overflows is past the end of T, above target if it
+            // overflowed upwards and below it otherwise
+            let ordering = match value(low).checked_add(&value(high)) {
+                Some(sum) => sum.cmp(&target),
+                None if value(high) > T::zero() => Ordering::Greater,
+                None => Ordering::Less
+            };
+            if ordering == Ordering::Less {
+                low += 1;
+            } else if ordering == Ordering::Greater {
+                high -= 1;
+            } else {
+                results.push(found(chosen, &[order[low], order[high]]));
+                if first_only {
+                    return;
+                }
+                let (low_value, high_value) = (value(low), value(high));
+                while low < high && value(low) == low_value {
+                    low += 1;
+                }
+                while low < high && value(high) == high_value {
+                    high -= 1;
+                }
+            }
+        }
+        return;
+    }
+
+    for i in from..order.len() {
+        if i > from && value(i) == value(i - 1) {
+            continue;
+        }
+        // Nothing is left to find once an element can't be taken off the target,
+        // like a value larger than what remains of an unsigned target
+        let remaining = match target.checked_sub(&value(i)) {
+            Some(remaining) => remaining,
+            None => continue
+        };
+        chosen.push(order[i]);
+        sorted_search(numbers, order, k - 1, remaining, i + 1, chosen, results, first_only);
+        chosen.pop();
+        if first_only && !results.is_empty() {
+            return;
+        }
+    }
+}
+
+// Same recursion in index order, the final pair is a single pass remembering
+// the values seen so far
+#[cfg(test)]
+fn hashed_search<T>(numbers: &[T], k: usize, target: T, from: usize, chosen: &mut Vec<usize>) -> Option<Vec<usize>>
+where T: PrimInt + Hash {
+    if k == 0 {
+        return if target == T::zero() { Some(found(chosen, &[])) } else { None };
+    }
+
+    if k == 1 {
+        return (from..numbers.len()).find(|i| numbers[*i] == target).map(|i| found(chosen, &[i]));
+    }
+
+    if k == 2 {
+        let mut seen: HashMap<T, usize> = HashMap::new();
+        for (j, number) in numbers.iter().enumerate().skip(from) {
+            if let Some(i) = target.checked_sub(number).and_then(|x| seen.get(&x)) {
+                return Some(found(chosen, &[*i, j]));
+            }
+            seen.entry(*number).or_insert(j);
+        }
+        return None;
+    }
+
+    for i in from..numbers.len() {
+        let remaining = match target.checked_sub(&numbers[i]) {
+            Some(remaining) => remaining,
+            None => continue
+        };
+        chosen.push(i);
+        let solution = hashed_search(numbers, k - 1, remaining, i + 1, chosen);
+        chosen.pop();
+        if solution.is_some() {
+            return solution;
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn first_test() {
+        let numbers = vec![1721, 979, 366, 299, 675, 1456];
+        assert_eq!(Some(vec![0, 3]), first(&numbers, 2, 2020));
+        assert_eq!(Some(vec![0, 3]), first_hashed(&numbers, 2, 2020));
+        assert_eq!(Some(vec![1, 2, 4]), first(&numbers, 3, 2020));
+        assert_eq!(Some(vec![1, 2, 4]), first_hashed(&numbers, 3, 2020));
+        assert_eq!(vec![979, 366, 675], values(&numbers, &[1, 2, 4]));
+
+        // An element can't pair with itself
+        assert_eq!(None, first(&[1010, 5], 2, 2020));
+        assert_eq!(None, first_hashed(&[1010, 5], 2, 2020));
+        assert_eq!(Some(vec![0, 2]), first(&[1010, 5, 1010], 2, 2020));
+        assert_eq!(Some(vec![0, 2]), first_hashed(&[1010, 5, 1010], 2, 2020));
+
+        assert_eq!(Some(vec![1]), first(&numbers, 1, 979));
+        assert_eq!(Some(vec![]), first(&numbers, 0, 0));
+        assert_eq!(None, first(&numbers, 7, 2020));
+        assert_eq!(None, first_hashed(&numbers, 7, 2020));
+    }
+
+    #[test]
+    fn unsigned_test() {
+        // Elements larger than what is left of the target can't be subtracted
+        assert_eq!(None, first_hashed(&[10u32, 3], 2, 5));
+        assert_eq!(None, first(&[10u32, 3], 2, 5));
+        assert_eq!(Some(vec![1, 2]), first_hashed(&[10u32, 3, 2], 2, 5));
+        assert_eq!(Some(vec![1, 2]), first(&[10u32, 3, 2], 2, 5));
+        assert_eq!(Some(vec![1, 2, 3]), first(&[10u32, 1, 3, 1], 3, 5));
+        assert_eq!(Some(vec![1, 2, 3]), first_hashed(&[10u32, 1, 3, 1], 3, 5));
+        assert_eq!(vec![vec![0, 2], vec![1, 3]], all(&[1u8, 2, 4, 3], 2, 5));
+
+        // Pair sums past u8::MAX
+        assert_eq!(Some(vec![0, 2]), first(&[200u8, 250, 50], 2, 250));
+        assert_eq!(None, first(&[200u8, 250, 100], 2, 255));
+    }
+
+    #[test]
+    fn extreme_signed_test() {
+        assert_eq!(Some(vec![0, 1]), first(&[i8::MAX, i8::MIN], 2, -1));
+        assert_eq!(None, first(&[i8::MAX, 100, i8::MIN], 2, 100));
+        assert_eq!(None, first_hashed(&[i8::MIN, 100], 2, 100));
+    }
+
+    #[test]
+    fn all_test() {
+        let numbers: Vec<i64> = vec![1, 0, -1, 0, -2, 2];
+        let mut solutions: Vec<Vec<i64>> = all(&numbers, 4, 0)
+            .iter()
+            .map(|x| {
+                let mut quad = values(&numbers, x);
+                quad.sort();
+                quad
+            })
+            .collect();
+        solutions.sort();
+        assert_eq!(vec![vec![-2, -1, 1, 2], vec![-2, 0, 0, 2], vec![-1, 0, 0, 1]], solutions);
+
+        assert_eq!(1, all(&[1, 1, 1], 2, 2).len());
+        assert_eq!(2, all(&[1, 2, 3, 4], 2, 5).len());
+        assert_eq!(0, all(&[1, 2, 3, 4], 2, 100).len());
+    }
+}
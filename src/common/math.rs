@@ -0,0 +1,188 @@
+use std::collections::HashMap;
+
+// (a * b) % modulus without overflowing, the product is widened to u128
+pub fn mod_mul(a: u64, b: u64, modulus: u64) -> u64 {
+    ((a as u128 * b as u128) % modulus as u128) as u64
+}
+
+// base^exponent % modulus by repeated squaring
+pub fn mod_pow(base: u64, exponent: u64, modulus: u64) -> u64 {
+    if modulus == 1 {
+        return 0;
+    }
+
+    let mut result: u64 = 1;
+    let mut base = base % modulus;
+    let mut exponent = exponent;
+
+    while exponent > 0 {
+        if exponent & 1 == 1 {
+            result = mod_mul(result, base, modulus);
+        }
+        base = mod_mul(base, base, modulus);
+        exponent >>= 1;
+    }
+
+    result
+}
+
+// Smallest x such that base^x % modulus == target, via baby-step giant-step.
+// With m = ceil(sqrt(modulus)) write x = i * m - j, the baby steps remember
+// target * base^j for j < m and the giant steps look up base^(i * m).
+// Needs base coprime to modulus, returns None if no such x exists or modulus is 0
+pub fn discrete_log(base: u64, target: u64, modulus: u64) -> Option<u64> {
+    if modulus == 0 {
+        return None;
+    }
+
+    let target = target % modulus;
+    if target == 1 % modulus {
+        return Some(0);
+    }
+
+    let m = (modulus as f64).sqrt().ceil() as u64;
+    let mut baby_steps: HashMap<u64, u64> = HashMap::new();
+    let mut value = target;
+    for j in 0..m {
+        // Later j overwrite earlier ones, larger j gives the smaller x
+        baby_steps.insert(value, j);
+        value = mod_mul(value, base, modulus);
+    }
+
+    let giant_step = mod_pow(base, m, modulus);
+    let mut value = 1;
+    for i in 1..(m + 1) {
+        value = mod_mul(value, giant_step, modulus);
+        if let Some(j) = baby_steps.get(&value) {
+            log::debug!("Found base^({} * {}) == target * base^{}", i, m, j);
+            return Some(i * m - j);
+        }
+    }
+
+    None
+}
+
+// (gcd, x, y) with a * x + b * y == gcd, the gcd is never negative
+pub fn extended_gcd(a: i128, b: i128) -> (i128, i128, i128) {
+    let (mut old_r, mut r) = (a, b);
+    let (mut old_x, mut x) = (1, 0);
+    let (mut old_y, mut y) = (0, 1);
+
+    while r != 0 {
+        let quotient = old_r / r;
+        let next_r = old_r - quotient * r;
+        old_r = r;
+        r = next_r;
+        let next_x = old_x - quotient * x;
+        old_x = x;
+        x = next_x;
+        let next_y = old_y - quotient * y;
+        old_y = y;
+        y = next_y;
+    }
+
+    if old_r < 0 {
+        return (-old_r, -old_x, -old_y);
+    }
+    (old_r, old_x, old_y)
+}
+
+// x in 0..modulus with a * x % modulus == 1, None unless a and modulus are coprime
+pub fn mod_inverse(a: i128, modulus: i128) -> Option<i128> {
+    let (gcd, x, _) = extended_gcd(a.rem_euclid(modulus), modulus);
+    if gcd != 1 {
+        return None;
+    }
+    Some(x.rem_euclid(modulus))
+}
+
+// Chinese remainder theorem over (residue, modulus) pairs. The moduli don't
+// need to be coprime, each pair is merged into the running solution and None
+// comes back as soon as two of them disagree. Returns (x, lcm of the moduli)
+// with x the smallest non negative solution, every other one is x + k * lcm.
+// Intermediate products are of the lcm with a modulus, so keep the lcm within 64 bits
+pub fn crt(congruences: &[(i128, i128)]) -> Option<(i128, i128)> {
+    let mut x: i128 = 0;
+    let mut lcm: i128 = 1;
+
+    for (residue, modulus) in congruences {
+        let residue = residue.rem_euclid(*modulus);
+        let (gcd, _, _) = extended_gcd(lcm, *modulus);
+        let difference = residue - x;
+        if difference % gcd != 0 {
+            log::debug!("x = {} mod {} contradicts x = {} mod {}", residue, modulus, x, lcm);
+            return None;
+        }
+
+        // Solve lcm * k == difference (mod modulus), then step x by lcm * k
+        let step = modulus / gcd;
+        let inverse = mod_inverse(lcm / gcd, step).unwrap();
+        let k = ((difference / gcd).rem_euclid(step) * inverse) % step;
+        x += lcm * k;
+        lcm *= step;
+        x = x.rem_euclid(lcm);
+    }
+
+    Some((x, lcm))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn mod_pow_test() {
+        assert_eq!(1, mod_pow(7, 0, 13));
+        assert_eq!(5764801, mod_pow(7, 8, 20201227));
+        assert_eq!(0, mod_pow(5, 3, 1));
+        assert_eq!(mod_mul(u64::MAX - 1, u64::MAX - 1, u64::MAX), mod_pow(u64::MAX - 1, 2, u64::MAX));
+    }
+
+    #[test]
+    fn discrete_log_test() {
+        assert_eq!(Some(8), discrete_log(7, 5764801, 20201227));
+        assert_eq!(Some(11), discrete_log(7, 17807724, 20201227));
+        assert_eq!(Some(0), discrete_log(3, 1, 7));
+        assert_eq!(None, discrete_log(2, 3, 7));
+        assert_eq!(None, discrete_log(7, 5764801, 0));
+
+        for x in 0..30 {
+            assert_eq!(Some(x), discrete_log(3, mod_pow(3, x, 31), 31));
+        }
+    }
+
+    #[test]
+    fn extended_gcd_test() {
+        assert_eq!((2, -9, 47), extended_gcd(240, 46));
+        assert_eq!((6, 0, -1), extended_gcd(0, -6));
+        for (a, b) in [(35, 15), (-35, 15), (17, 5), (1, 1), (12, 0)] {
+            let (gcd, x, y) = extended_gcd(a, b);
+            assert_eq!(num::integer::gcd(a, b), gcd);
+            assert_eq!(gcd, a * x + b * y);
+        }
+    }
+
+    #[test]
+    fn mod_inverse_test() {
+        assert_eq!(Some(4), mod_inverse(3, 11));
+        assert_eq!(Some(7), mod_inverse(-3, 11));
+        assert_eq!(None, mod_inverse(6, 9));
+        assert_eq!(Some(0), mod_inverse(5, 1));
+    }
+
+    #[test]
+    fn crt_test() {
+        assert_eq!(Some((23, 105)), crt(&[(2, 3), (3, 5), (2, 7)]));
+        assert_eq!(Some((0, 1)), crt(&[]));
+
+        // Non coprime moduli, consistent and not
+        assert_eq!(Some((10, 12)), crt(&[(4, 6), (2, 4)]));
+        assert_eq!(None, crt(&[(1, 6), (2, 4)]));
+        assert_eq!(Some((3, 6)), crt(&[(3, 6), (-3, 6), (1, 2)]));
+
+        // Large coprime moduli whose product needs more than 32 bits
+        let (x, lcm) = crt(&[(-1, 1_000_000_007), (-1, 998_244_353)]).unwrap();
+        assert_eq!(1_000_000_007 * 998_244_353 - 1, x);
+        assert_eq!(1_000_000_007 * 998_244_353, lcm);
+    }
+}
@@ -0,0 +1,77 @@
+// Cache for recursive solvers, each argument is computed once
+
+use std::collections::{HashMap, HashSet};
+use std::hash::Hash;
+
+// Results keyed by argument. The compute closure gets the memo back so it can
+// recurse into it, and keys still being computed are tracked so a cycle fails
+// loudly instead of overflowing the stack
+#[derive(Clone, Debug)]
+pub struct Memo<K, V> {
+    cache: HashMap<K, V>,
+    pending: HashSet<K>
+}
+
+impl<K, V> Memo<K, V>
+where K: Clone + Eq + Hash, V: Clone {
+    pub fn new() -> Memo<K, V> {
+        Memo {cache: HashMap::new(), pending: HashSet::new()}
+    }
+
+    // Cached value for key, running compute the first time it's asked for
+    pub fn get(&mut self, key: K, compute: impl FnOnce(&mut Memo<K, V>) -> V) -> V {
+        if let Some(value) = self.cache.get(&key) {
+            return value.clone();
+        }
+
+        assert!(self.pending.insert(key.clone()), "Memo recursed into a key that is still being computed");
+        let value = compute(self);
+        self.pending.remove(&key);
+        self.cache.insert(key, value.clone());
+        value
+    }
+}
+
+impl<K, V> Default for Memo<K, V>
+where K: Clone + Eq + Hash, V: Clone {
+    fn default() -> Memo<K, V> {
+        Memo::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::Cell;
+
+    fn fibonacci(n: u64, memo: &mut Memo<u64, u64>, calls: &Cell<u32>) -> u64 {
+        memo.get(n, |memo| {
+            calls.set(calls.get() + 1);
+            if n < 2 {
+                return n;
+            }
+            fibonacci(n - 1, memo, calls) + fibonacci(n - 2, memo, calls)
+        })
+    }
+
+    #[test]
+    fn memo_test() {
+        let mut memo = Memo::new();
+        let calls = Cell::new(0);
+        assert_eq!(12586269025, fibonacci(50, &mut memo, &calls));
+        assert_eq!(51, calls.get());
+
+        // Already cached, nothing new is computed
+        assert_eq!(832040, fibonacci(30, &mut memo, &calls));
+        assert_eq!(51, calls.get());
+    }
+
+    #[test]
+    #[should_panic]
+    fn memo_cycle_test() {
+        fn looping(n: u32, memo: &mut Memo<u32, u32>) -> u32 {
+            memo.get(n, |memo| looping((n + 1) % 3, memo))
+        }
+        looping(0, &mut Memo::new());
+    }
+}
@@ -0,0 +1,315 @@
+// Small parser combinators for puzzle lines. A parser is any function taking
+// a Cursor and returning the parsed value along with the cursor past it, or a
+// Failure saying what was expected and where. Build line parsers out of the
+// primitives here, then run them with parse_all or parse_lines
+
+use std::fmt;
+use std::str::FromStr;
+
+use crate::common::convertor::ParseError;
+
+// Position in the text being parsed, cheap to copy so alternatives can
+// backtrack by simply reusing an old cursor
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct Cursor<'a> {
+    text: &'a str,
+    position: usize
+}
+
+impl<'a> Cursor<'a> {
+    pub fn new(text: &'a str) -> Cursor<'a> {
+        Cursor {text, position: 0}
+    }
+
+    // Byte offset into the text
+    pub fn position(&self) -> usize {
+        self.position
+    }
+
+    pub fn rest(&self) -> &'a str {
+        &self.text[self.position..]
+    }
+
+    pub fn is_at_end(&self) -> bool {
+        self.position == self.text.len()
+    }
+
+    fn advance(&self, bytes: usize) -> Cursor<'a> {
+        Cursor {text: self.text, position: self.position + bytes}
+    }
+
+    fn fail<T>(&self, expected: impl fmt::Display) -> Step<'a, T> {
+        Err(Failure {position: self.position, expected: expected.to_string()})
+    }
+}
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct Failure {
+    pub position: usize,
+    pub expected: String
+}
+
+impl fmt::Display for Failure {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "expected {} at column {}", self.expected, self.position + 1)
+    }
+}
+
+pub type Step<'a, T> = Result<(T, Cursor<'a>), Failure>;
+
+// Exactly the given text
+pub fn literal<'a>(expected: &'static str) -> impl Fn(Cursor<'a>) -> Step<'a, &'a str> {
+    move |input: Cursor<'a>| {
+        if input.rest().starts_with(expected) {
+            return Ok((&input.rest()[..expected.len()], input.advance(expected.len())));
+        }
+        input.fail(format!("'{}'", expected))
+    }
+}
+
+// Any single character
+pub fn character<'a>() -> impl Fn(Cursor<'a>) -> Step<'a, char> {
+    satisfy(|_| true, "a character")
+}
+
+// A single character matching predicate
+pub fn satisfy<'a, P>(predicate: P, expected: &'static str) -> impl Fn(Cursor<'a>) -> Step<'a, char>
+where P: Fn(char) -> bool {
+    move |input: Cursor<'a>| {
+        match input.rest().chars().next() {
+            Some(c) if predicate(c) => Ok((c, input.advance(c.len_utf8()))),
+            _ => input.fail(expected)
+        }
+    }
+}
+
+// The longest run of at least one character matching predicate
+pub fn take_while1<'a, P>(predicate: P, expected: &'static str) -> impl Fn(Cursor<'a>) -> Step<'a, &'a str>
+where P: Fn(char) -> bool {
+    move |input: Cursor<'a>| {
+        let rest = input.rest();
+        let length = rest.find(|c: char| !predicate(c)).unwrap_or(rest.len());
+        if length == 0 {
+            return input.fail(expected);
+        }
+        Ok((&rest[..length], input.advance(length)))
+    }
+}
+
+// Run of letters
+pub fn word<'a>() -> impl Fn(Cursor<'a>) -> Step<'a, &'a str> {
+    take_while1(|c| c.is_alphabetic(), "a word")
+}
+
+// Run of whitespace, at least one character
+pub fn spaces<'a>() -> impl Fn(Cursor<'a>) -> Step<'a, &'a str> {
+    take_while1(|c| c.is_whitespace(), "whitespace")
+}
+
+// Decimal integer with an optional + or - sign
+pub fn integer<'a, T>() -> impl Fn(Cursor<'a>) -> Step<'a, T>
+where T: FromStr {
+    move |input: Cursor<'a>| {
+        let rest = input.rest();
+        let sign = if rest.starts_with('+') || rest.starts_with('-') { 1 } else { 0 };
+        let digits = rest[sign..].find(|c: char| !c.is_ascii_digit()).unwrap_or(rest.len() - sign);
+        if digits == 0 {
+            return input.fail("an integer");
+        }
+
+        // from_str rejects a leading + for some types
+        let number = rest[..sign + digits].trim_start_matches('+');
+        match number.parse::<T>() {
+            Ok(value) => Ok((value, input.advance(sign + digits))),
+            Err(_) => input.fail(format!("an integer in range, got {}", number))
+        }
+    }
+}
+
+pub fn map<'a, A, B, P, F>(parser: P, function: F) -> impl Fn(Cursor<'a>) -> Step<'a, B>
+where P: Fn(Cursor<'a>) -> Step<'a, A>, F: Fn(A) -> B {
+    move |input: Cursor<'a>| {
+        let (value, rest) = parser(input)?;
+        Ok((function(value), rest))
+    }
+}
+
+// Like map for a conversion that can fail, the failure points at the start of
+// the parsed value and the error says what was expected instead
+pub fn try_map<'a, A, B, E, P, F>(parser: P, function: F) -> impl Fn(Cursor<'a>) -> Step<'a, B>
+where P: Fn(Cursor<'a>) -> Step<'a, A>, F: Fn(A) -> Result<B, E>, E: fmt::Display {
+    move |input: Cursor<'a>| {
+        let (value, rest) = parser(input)?;
+        match function(value) {
+            Ok(converted) => Ok((converted, rest)),
+            Err(error) => input.fail(error)
+        }
+    }
+}
+
+// Always succeeds, None if parser doesn't match here
+pub fn optional<'a, T, P>(parser: P) -> impl Fn(Cursor<'a>) -> Step<'a, Option<T>>
+where P: Fn(Cursor<'a>) -> Step<'a, T> {
+    move |input: Cursor<'a>| {
+        match parser(input) {
+            Ok((value, rest)) => Ok((Some(value), rest)),
+            Err(_) => Ok((None, input))
+        }
+    }
+}
+
+pub fn pair<'a, A, B, PA, PB>(first: PA, second: PB) -> impl Fn(Cursor<'a>) -> Step<'a, (A, B)>
+where PA: Fn(Cursor<'a>) -> Step<'a, A>, PB: Fn(Cursor<'a>) -> Step<'a, B> {
+    move |input: Cursor<'a>| {
+        let (a, rest) = first(input)?;
+        let (b, rest) = second(rest)?;
+        Ok(((a, b), rest))
+    }
+}
+
+pub fn triple<'a, A, B, C, PA, PB, PC>(first: PA, second: PB, third: PC) -> impl Fn(Cursor<'a>) -> Step<'a, (A, B, C)>
+where PA: Fn(Cursor<'a>) -> Step<'a, A>, PB: Fn(Cursor<'a>) -> Step<'a, B>, PC: Fn(Cursor<'a>) -> Step<'a, C> {
+    move |input: Cursor<'a>| {
+        let (a, rest) = first(input)?;
+        let (b, rest) = second(rest)?;
+        let (c, rest) = third(rest)?;
+        Ok(((a, b, c), rest))
+    }
+}
+
+// Value of parser, after skipping prefix
+pub fn preceded<'a, A, B, PA, PB>(prefix: PA, parser: PB) -> impl Fn(Cursor<'a>) -> Step<'a, B>
+where PA: Fn(Cursor<'a>) -> Step<'a, A>, PB: Fn(Cursor<'a>) -> Step<'a, B> {
+    map(pair(prefix, parser), |(_, b)| b)
+}
+
+// Value of parser, then skipping suffix
+pub fn terminated<'a, A, B, PA, PB>(parser: PA, suffix: PB) -> impl Fn(Cursor<'a>) -> Step<'a, A>
+where PA: Fn(Cursor<'a>) -> Step<'a, A>, PB: Fn(Cursor<'a>) -> Step<'a, B> {
+    map(pair(parser, suffix), |(a, _)| a)
+}
+
+// First alternative that matches. When both fail the failure that got further
+// is reported, or both expectations if they failed at the same spot
+pub fn alt<'a, T, PA, PB>(first: PA, second: PB) -> impl Fn(Cursor<'a>) -> Step<'a, T>
+where PA: Fn(Cursor<'a>) -> Step<'a, T>, PB: Fn(Cursor<'a>) -> Step<'a, T> {
+    move |input: Cursor<'a>| {
+        let first_failure = match first(input) {
+            Ok(result) => return Ok(result),
+            Err(failure) => failure
+        };
+        let second_failure = match second(input) {
+            Ok(result) => return Ok(result),
+            Err(failure) => failure
+        };
+
+        if first_failure.position == second_failure.position {
+            let expected = format!("{} or {}", first_failure.expected, second_failure.expected);
+            return Err(Failure {position: first_failure.position, expected});
+        }
+        Err(if first_failure.position > second_failure.position { first_failure } else { second_failure })
+    }
+}
+
+// One or more items with separator between them
+pub fn separated<'a, T, S, P, PS>(item: P, separator: PS) -> impl Fn(Cursor<'a>) -> Step<'a, Vec<T>>
+where P: Fn(Cursor<'a>) -> Step<'a, T>, PS: Fn(Cursor<'a>) -> Step<'a, S> {
+    move |input: Cursor<'a>| {
+        let (first, mut rest) = item(input)?;
+        let mut items: Vec<T> = vec![first];
+
+        // A separator not followed by an item is left for the next parser
+        while let Ok((_, after_separator)) = separator(rest) {
+            match item(after_separator) {
+                Ok((value, after_item)) => {
+                    items.push(value);
+                    rest = after_item;
+                },
+                Err(_) => break
+            }
+        }
+        Ok((items, rest))
+    }
+}
+
+// Run parser over the whole of text, anything left over is an error
+pub fn parse_all<'a, T, P>(parser: P, text: &'a str) -> Result<T, Failure>
+where P: Fn(Cursor<'a>) -> Step<'a, T> {
+    let (value, rest) = parser(Cursor::new(text))?;
+    if !rest.is_at_end() {
+        return Err(Failure {position: rest.position(), expected: "end of line".to_string()});
+    }
+    Ok(value)
+}
+
+// parse_all on every line, the error has the 1-based line number and the
+// text from where parsing failed
+pub fn parse_lines<'a, T, P, S>(parser: P, lines: &'a [S]) -> Result<Vec<T>, ParseError>
+where P: Fn(Cursor<'a>) -> Step<'a, T>, S: AsRef<str> {
+    let mut values: Vec<T> = Vec::with_capacity(lines.len());
+    for (index, line) in lines.iter().enumerate() {
+        let line = line.as_ref();
+        match parse_all(&parser, line) {
+            Ok(value) => values.push(value),
+            Err(failure) => return Err(ParseError::new(index + 1, None, &line[failure.position..], failure))
+        }
+    }
+    Ok(values)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn primitives_test() {
+        assert_eq!(Ok("mask"), parse_all(literal("mask"), "mask"));
+        assert_eq!(Ok('x'), parse_all(character(), "x"));
+        assert_eq!(Ok("shiny"), parse_all(word(), "shiny"));
+        assert_eq!(Ok(-42), parse_all(integer::<i32>(), "-42"));
+        assert_eq!(Ok(7), parse_all(integer::<u8>(), "+7"));
+        assert_eq!(Ok("101X"), parse_all(take_while1(|c| "01X".contains(c), "mask bits"), "101X"));
+
+        let failure = parse_all(integer::<u8>(), "300").unwrap_err();
+        assert_eq!(0, failure.position);
+        assert_eq!(Err(Failure {position: 0, expected: "an integer".to_string()}), parse_all(integer::<i32>(), "+"));
+        assert_eq!(Err(Failure {position: 2, expected: "end of line".to_string()}), parse_all(integer::<i32>(), "12ab"));
+        assert_eq!("expected 'mem' at column 1", parse_all(literal("mem"), "mask").unwrap_err().to_string());
+    }
+
+    #[test]
+    fn combinators_test() {
+        let assignment = pair(preceded(literal("mem["), integer::<u64>()), preceded(literal("] = "), integer::<i64>()));
+        assert_eq!(Ok((8, 11)), parse_all(&assignment, "mem[8] = 11"));
+        assert_eq!(Err(Failure {position: 5, expected: "'] = '".to_string()}), parse_all(&assignment, "mem[8]= 11"));
+
+        let list = separated(integer::<i32>(), literal(", "));
+        assert_eq!(Ok(vec![1, 2, 3]), parse_all(&list, "1, 2, 3"));
+        let trailing = terminated(separated(integer::<i32>(), literal(",")), literal(","));
+        assert_eq!(Ok(vec![1, 2]), parse_all(trailing, "1,2,"));
+
+        let sign = alt(map(literal("on"), |_| true), map(literal("off"), |_| false));
+        assert_eq!(Ok(false), parse_all(&sign, "off"));
+        assert_eq!("expected 'on' or 'off' at column 1", parse_all(&sign, "of").unwrap_err().to_string());
+
+        let even = try_map(integer::<i32>(), |x| if x % 2 == 0 { Ok(x) } else { Err("an even number") });
+        assert_eq!(Ok(vec![2, 4]), parse_all(separated(&even, literal(",")), "2,4"));
+        assert_eq!(Err(Failure {position: 1, expected: "an even number".to_string()}), parse_all(preceded(literal("="), &even), "=13"));
+
+        let range = triple(integer::<i32>(), optional(literal("-")), integer::<i32>());
+        assert_eq!(Ok((1, Some("-"), 3)), parse_all(&range, "1-3"));
+        assert_eq!(Ok((1, 3)), parse_all(pair(integer::<i32>(), preceded(spaces(), integer::<i32>())), "1  3"));
+    }
+
+    #[test]
+    fn parse_lines_test() {
+        let lines = vec!["F10", "N3", "R90"];
+        let parser = pair(satisfy(|c| c.is_ascii_uppercase(), "an action"), integer::<i32>());
+        assert_eq!(Ok(vec![('F', 10), ('N', 3), ('R', 90)]), parse_lines(&parser, &lines));
+
+        let error = parse_lines(&parser, &["F10", "x3"]).unwrap_err();
+        assert_eq!(2, error.line);
+        assert_eq!("x3", error.token);
+        assert_eq!("expected an action at column 1", error.message);
+    }
+}
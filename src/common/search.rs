@@ -1,23 +1,75 @@
-pub fn binary_search<T>(vector: &Vec<T>, entry: T) -> i32 
-where T: PartialOrd {
-    let size: usize = vector.len();
-    
-    let mut min: i32 = 0;
-    let mut max: i32 = size as i32 - 1 ;
-    while min <= max {
-        let mid = (((min + max) as f32) / 2.0).floor() as i32;
-        log::debug!("Values min: {}, max {}, mid {}, size: {}", min, max, mid, size);
-        
-        if vector[mid as usize] < entry {
+// First index for which predicate is false, the slice must be partitioned so
+// every element satisfying predicate comes before every element that does not
+#[cfg(test)]
+pub fn partition_point<T, P>(slice: &[T], predicate: P) -> usize
+where P: Fn(&T) -> bool {
+    let mut min: usize = 0;
+    let mut max: usize = slice.len();
+
+    while min < max {
+        let mid = min + (max - min) / 2;
+        log::trace!("Values min: {}, max {}, mid {}, size: {}", min, max, mid, slice.len());
+
+        if predicate(&slice[mid]) {
             min = mid + 1;
-        } else if vector[mid as usize] > entry {
-            max = mid - 1;
         } else {
-            return mid;
+            max = mid;
         }
     }
 
-    return -1;
+    min
+}
+
+// First index whose element is not less than entry, slice.len() if none
+#[cfg(test)]
+pub fn lower_bound<T>(slice: &[T], entry: &T) -> usize
+where T: PartialOrd {
+    partition_point(slice, |x| x < entry)
+}
+
+// First index whose element is greater than entry, slice.len() if none
+#[cfg(test)]
+pub fn upper_bound<T>(slice: &[T], entry: &T) -> usize
+where T: PartialOrd {
+    partition_point(slice, |x| x <= entry)
+}
+
+// Index of entry in a sorted slice, the first one if it appears several times
+#[cfg(test)]
+pub fn binary_search<T>(slice: &[T], entry: &T) -> Option<usize>
+where T: PartialOrd {
+    let index = lower_bound(slice, entry);
+    if index < slice.len() && slice[index] == *entry {
+        return Some(index);
+    }
+    None
+}
+
+// Smallest x in low..=high for which predicate holds, assuming predicate is
+// false up to some point and true from there on. Binary search on the answer
+pub fn first_true<P>(low: i64, high: i64, predicate: P) -> Option<i64>
+where P: Fn(i64) -> bool {
+    if low > high {
+        return None;
+    }
+
+    // Widened so high - low cannot overflow for ranges spanning all of i64
+    let mut min = low as i128;
+    let mut max = high as i128 + 1;
+
+    while min < max {
+        let mid = min + (max - min) / 2;
+        if predicate(mid as i64) {
+            max = mid;
+        } else {
+            min = mid + 1;
+        }
+    }
+
+    if min > high as i128 {
+        return None;
+    }
+    Some(min as i64)
 }
 
 #[cfg(test)]
@@ -31,12 +83,42 @@ mod tests {
         let multiple = vec![1,2,3,4,5,6,7,8,9,10];
 
         // Tests
-        assert_eq!(-1, binary_search(&empty, 0));
-        assert_eq!(0, binary_search(&single, 1));
-        assert_eq!(-1, binary_search(&single, 2));
-        
+        assert_eq!(None, binary_search(&empty, &0));
+        assert_eq!(Some(0), binary_search(&single, &1));
+        assert_eq!(None, binary_search(&single, &2));
+        assert_eq!(None, binary_search(&multiple, &0));
+        assert_eq!(None, binary_search(&multiple, &11));
+
         for entry in multiple.iter() {
-            assert_eq!(entry - 1, binary_search(&multiple, *entry));
+            assert_eq!(Some((entry - 1) as usize), binary_search(&multiple, entry));
         }
+
+        let huge = vec![i64::MIN, -1, 0, i64::MAX];
+        assert_eq!(Some(3), binary_search(&huge, &i64::MAX));
+        assert_eq!(Some(0), binary_search(&huge, &i64::MIN));
+    }
+
+    #[test]
+    fn bounds_test() {
+        let repeated = vec![1, 2, 2, 2, 5];
+        assert_eq!(1, lower_bound(&repeated, &2));
+        assert_eq!(4, upper_bound(&repeated, &2));
+        assert_eq!(4, lower_bound(&repeated, &3));
+        assert_eq!(4, upper_bound(&repeated, &3));
+        assert_eq!(0, lower_bound(&repeated, &0));
+        assert_eq!(5, upper_bound(&repeated, &5));
+        assert_eq!(Some(1), binary_search(&repeated, &2));
+        assert_eq!(1, partition_point(&repeated, |x| *x < 2));
+        assert_eq!(5, partition_point(&repeated, |x| x % 7 != 0));
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn first_true_test() {
+        assert_eq!(Some(45), first_true(0, 100, |x| x * x >= 2000));
+        assert_eq!(None, first_true(0, 10, |x| x > 10));
+        assert_eq!(Some(0), first_true(0, 10, |_| true));
+        assert_eq!(None, first_true(5, 4, |_| true));
+        assert_eq!(Some(i64::MAX), first_true(i64::MIN, i64::MAX, |x| x == i64::MAX));
+        assert_eq!(Some(-7), first_true(i64::MIN, i64::MAX, |x| x >= -7));
+    }
+}
@@ -0,0 +1,144 @@
+// Searching for contiguous runs of a slice by their sum
+use std::collections::HashMap;
+use std::hash::Hash;
+use num::PrimInt;
+
+// numbers[start..end] of the slice it was found in
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct Subarray<'a, T> {
+    pub start: usize,
+    pub end: usize,
+    numbers: &'a [T]
+}
+
+impl<'a, T> Subarray<'a, T>
+where T: PrimInt {
+    pub fn new(numbers: &'a [T], start: usize, end: usize) -> Subarray<'a, T> {
+        Subarray {start, end, numbers}
+    }
+
+    pub fn values(&self) -> &'a [T] {
+        &self.numbers[self.start..self.end]
+    }
+
+    pub fn len(&self) -> usize {
+        self.end - self.start
+    }
+
+    #[cfg(test)]
+    pub fn is_empty(&self) -> bool {
+        self.start == self.end
+    }
+
+    pub fn sum(&self) -> T {
+        self.values().iter().fold(T::zero(), |total, x| total + *x)
+    }
+
+    pub fn min(&self) -> Option<T> {
+        self.values().iter().min().copied()
+    }
+
+    pub fn max(&self) -> Option<T> {
+        self.values().iter().max().copied()
+    }
+}
+
+// First run of at least min_length numbers that sums to target, the one that
+// ends earliest. Picks the two pointer walk when no number is negative
+pub fn find_sum<T>(numbers: &[T], target: T, min_length: usize) -> Option<Subarray<'_, T>>
+where T: PrimInt + Hash {
+    if numbers.iter().all(|x| *x >= T::zero()) {
+        return find_sum_two_pointer(numbers, target, min_length);
+    }
+    find_sum_prefix(numbers, target, min_length)
+}
+
+// Window between two pointers, grow it while the sum is short and shrink it
+// from the front while it's over. Only valid when no number is negative, then
+// neither pointer ever has to move back
+pub fn find_sum_two_pointer<T>(numbers: &[T], target: T, min_length: usize) -> Option<Subarray<'_, T>>
+where T: PrimInt {
+    let mut start = 0;
+    let mut end = 0;
+    let mut sum = T::zero();
+
+    loop {
+        if sum == target && end - start >= min_length {
+            return Some(Subarray::new(numbers, start, end));
+        }
+        if end < numbers.len() && sum <= target {
+            sum = sum + numbers[end];
+            end += 1;
+        } else if start < end {
+            sum = sum - numbers[start];
+            start += 1;
+        } else {
+            return None;
+        }
+    }
+}
+
+// Works for any numbers. The run start..end sums to target when
+// prefix[end] - prefix[start] == target, remember where each prefix sum first
+// shows up and look the complement up as end moves along
+pub fn find_sum_prefix<T>(numbers: &[T], target: T, min_length: usize) -> Option<Subarray<'_, T>>
+where T: PrimInt + Hash {
+    let mut prefix: Vec<T> = Vec::with_capacity(numbers.len() + 1);
+    prefix.push(T::zero());
+    for number in numbers {
+        prefix.push(*prefix.last().unwrap() + *number);
+    }
+
+    let mut first_seen: HashMap<T, usize> = HashMap::new();
+    for end in min_length..prefix.len() {
+        // Only starts far enough back are candidates
+        first_seen.entry(prefix[end - min_length]).or_insert(end - min_length);
+        if let Some(start) = first_seen.get(&(prefix[end] - target)) {
+            return Some(Subarray::new(numbers, *start, end));
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn find_sum_test() {
+        let numbers: Vec<i64> = vec![35, 20, 15, 25, 47, 40, 62, 55, 65, 95, 102, 117, 150, 182];
+        for found in [find_sum_two_pointer(&numbers, 127, 2), find_sum_prefix(&numbers, 127, 2)] {
+            let range = found.unwrap();
+            assert_eq!((2, 6), (range.start, range.end));
+            assert_eq!(&[15, 25, 47, 40], range.values());
+            assert_eq!(127, range.sum());
+            assert_eq!(Some(15), range.min());
+            assert_eq!(Some(47), range.max());
+        }
+
+        // A single number only counts when min_length allows it
+        assert_eq!((4, 5), find_sum(&numbers, 47, 1).map(|x| (x.start, x.end)).unwrap());
+        assert_eq!(None, find_sum_two_pointer(&numbers, 47, 2));
+        assert_eq!(None, find_sum_prefix(&numbers, 47, 2));
+        assert_eq!(None, find_sum(&numbers, 1, 1));
+    }
+
+    #[test]
+    fn find_sum_zeros_test() {
+        let numbers: Vec<i32> = vec![3, 0, 0, 4, 5];
+        assert_eq!((0, 3), find_sum_two_pointer(&numbers, 3, 3).map(|x| (x.start, x.end)).unwrap());
+        assert_eq!((0, 3), find_sum_prefix(&numbers, 3, 3).map(|x| (x.start, x.end)).unwrap());
+        assert_eq!((1, 2), find_sum_two_pointer(&numbers, 0, 1).map(|x| (x.start, x.end)).unwrap());
+        assert!(find_sum_two_pointer(&numbers, 0, 0).unwrap().is_empty());
+    }
+
+    #[test]
+    fn find_sum_negative_test() {
+        let numbers: Vec<i32> = vec![10, 2, -2, -20, 10];
+        let range = find_sum(&numbers, -10, 2).unwrap();
+        assert_eq!((0, 4), (range.start, range.end));
+        assert_eq!(Some(-20), range.min());
+        assert_eq!((1, 4), find_sum(&numbers, -20, 1).map(|x| (x.start, x.end)).unwrap());
+        assert_eq!(None, find_sum(&numbers, 100, 1));
+    }
+}
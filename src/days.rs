@@ -12,4 +12,9 @@ pub mod day11;
 pub mod day12;
 pub mod day13;
 pub mod day14;
-pub mod day15;
\ No newline at end of file
+pub mod day15;
+pub mod day21;
+pub mod day22;
+pub mod day23;
+pub mod day24;
+pub mod day25;
\ No newline at end of file
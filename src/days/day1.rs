@@ -1,56 +1,40 @@
-use std::path::Path as Path;
-use std::iter::FromIterator;
-use std::collections::HashSet;
 use std::time::Instant;
 
 use crate::common::io;
 use crate::common::convertor;
-
-// Pick a numeber and binary search for corresponding pair for 2020 - number
-fn part1(numbers: &Vec<i16>) -> f32 {
-    log::info!("Running Part 1");
-    let lookup: HashSet<i16> = HashSet::from_iter(numbers.iter().cloned());
-
-    for number in numbers.iter() {
-        let find = 2020 - number;
-        log::debug!("Processing {}, looking for {}", number, find);
-
-        if lookup.contains(&find) {
-            log::info!("Found {}", find);
-            let result: f32 = *number as f32 * find as f32;
+use crate::common::ksum;
+
+// Multiply together the first k entries found that sum up to 2020
+fn product_of_entries(numbers: &[i32], k: usize) -> f32 {
+    match ksum::first(numbers, k, 2020) {
+        Some(indices) => {
+            let entries = ksum::values(numbers, &indices);
+            log::info!("Found entries {:?}", entries);
+            let result: f32 = entries.iter().map(|x| *x as f32).product();
             log::info!("Multiplication result is {}", result);
-            return result;
+            result
+        },
+        None => {
+            log::error!("Didnt find {} entries such that the sum is 2020", k);
+            -1.0
         }
     }
-    log::error!("Didnt find a pair such that the sum is 2020");
-    return -1.0;
 }
 
-// Pick a pair of numbers and try to find a third such that they sum up to 2020
-fn part2(numbers: &Vec<i16>) -> f32 {
-    log::info!("Running Part 2");
-    let lookup: HashSet<i16> = HashSet::from_iter(numbers.iter().cloned());
-    let n = numbers.len();
-
-    for i in 0..n {
-        for j in i+1..n {
-            let find = 2020 - numbers[i] - numbers[j];
-            log::debug!("Processing {} {}, looking for {}", numbers[i], numbers[j], find);
+// Find a pair of entries that sum up to 2020
+fn part1(numbers: &Vec<i32>) -> f32 {
+    log::info!("Running Part 1");
+    product_of_entries(numbers, 2)
+}
 
-            if lookup.contains(&find) {
-                log::info!("Found a trio {}, {} and {}", numbers[i], numbers[j], find);
-                let result = (numbers[i] as f32) * (numbers[j] as f32) * (find as f32);
-                log::info!("Multiplication result is {}", result);
-                return result;
-            }
-        }
-    }
-    log::error!("Didnt find a pair such that the sum is 2020");
-    return -1.0;
+// Find three entries that sum up to 2020
+fn part2(numbers: &Vec<i32>) -> f32 {
+    log::info!("Running Part 2");
+    product_of_entries(numbers, 3)
 }
 
-pub fn run(filename: impl AsRef<Path>) {
-    let numbers: Vec<i16> = convertor::vector_str_to_int(io::lines_from_file(filename));
+pub fn run(source: &io::InputSource) {
+    let numbers: Vec<i32> = convertor::parse_lines(&source.lines()).unwrap();
     let now = Instant::now();
     part1(&numbers);
     log::info!("Part 1: {}us", now.elapsed().as_micros());
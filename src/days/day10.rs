@@ -1,15 +1,14 @@
-use std::path::Path as Path;
 use std::time::Instant;
 use std::collections::HashMap;
 
 use crate::common::convertor;
 use crate::common::io;
 
-fn part1(adapters: &Vec<i16>) -> i32 {
+fn part1(adapters: &[i32]) -> i32 {
     log::info!("Running Part 1");
 
-    let mut count_dict: HashMap<i16, i16> = HashMap::new();
-    let mut ordered = adapters.clone();
+    let mut count_dict: HashMap<i32, i32> = HashMap::new();
+    let mut ordered = adapters.to_vec();
     ordered.sort();
 
     log::info!("Found that the ordered list of adapters is {:?} of length {}", ordered, ordered.len());
@@ -23,19 +22,19 @@ fn part1(adapters: &Vec<i16>) -> i32 {
     }
 
     log::info!("Found one diff cases to be {} and three diff to be {}", count_dict[&1] + 1, count_dict[&3]+1);
-    return (count_dict[&1] as i32 + 1) * (count_dict[&3] as i32 + 1);
+    return (count_dict[&1] + 1) * (count_dict[&3] + 1);
 }
 
-fn part2(adapters: &Vec<i16>) -> i64 {
+fn part2(adapters: &[i32]) -> i64 {
     log::info!("Running Part 2");
-    let mut sorted_adapters = adapters.clone();
+    let mut sorted_adapters = adapters.to_vec();
     sorted_adapters.sort();
 
-    let mut table: HashMap<i16, i64> = HashMap::new();
+    let mut table: HashMap<i32, i64> = HashMap::new();
     
     // ways to get to Max + 3 is always 1
-    let start: i16 = 0;
-    let max: i16 = *sorted_adapters.last().unwrap();
+    let start: i32 = 0;
+    let max: i32 = *sorted_adapters.last().unwrap();
 
     table.insert(start, 1);
 
@@ -62,8 +61,8 @@ fn part2(adapters: &Vec<i16>) -> i64 {
     return table[&max];
 }
 
-pub fn run(filename: impl AsRef<Path>) {
-    let adapters = convertor::vector_str_to_int(io::lines_from_file(filename));
+pub fn run(source: &io::InputSource) {
+    let adapters: Vec<i32> = convertor::parse_lines(&source.lines()).unwrap();
     let now = Instant::now();
     part1(&adapters);
     log::info!("Part 1: {}us", now.elapsed().as_micros());
@@ -77,14 +76,14 @@ mod tests {
 
     #[test]
     fn part1_test() {
-        let mut adapters: Vec<i16> = vec![28, 33, 18, 42, 31, 14, 46, 20, 48, 47, 24, 23, 49, 45, 19, 38, 39, 11, 1, 32, 25, 35, 8, 17, 7, 9, 4, 2, 34, 10, 3];
+        let mut adapters: Vec<i32> = vec![28, 33, 18, 42, 31, 14, 46, 20, 48, 47, 24, 23, 49, 45, 19, 38, 39, 11, 1, 32, 25, 35, 8, 17, 7, 9, 4, 2, 34, 10, 3];
         adapters.sort();
         assert_eq!(220, part1(&adapters));
     }
 
     #[test]
     fn part2_test() {
-        let mut adapters: Vec<i16> = vec![28, 33, 18, 42, 31, 14, 46, 20, 48, 47, 24, 23, 49, 45, 19, 38, 39, 11, 1, 32, 25, 35, 8, 17, 7, 9, 4, 2, 34, 10, 3];
+        let mut adapters: Vec<i32> = vec![28, 33, 18, 42, 31, 14, 46, 20, 48, 47, 24, 23, 49, 45, 19, 38, 39, 11, 1, 32, 25, 35, 8, 17, 7, 9, 4, 2, 34, 10, 3];
         adapters.sort();
         assert_eq!(19208, part2(&adapters));
     }
@@ -1,288 +1,67 @@
-use std::path::Path as Path;
 use std::time::Instant;
 
 use crate::common::io;
-
-fn generate_neighbors(x: i16, y: i16, x_max: i16, y_max: i16) -> Vec<(usize,usize)>{
-    let neighbors: Vec<(usize, usize)> = vec![
-        (x-1,y),
-        (x-1,y-1),
-        (x,y-1),
-        (x+1,y-1),
-        (x+1,y),
-        (x+1,y+1),
-        (x,y+1),
-        (x-1,y+1)
-    ].iter()
-    .filter(|&(i,j)| *i >= 0 && *j >= 0 && *i < x_max && *j < y_max)
-    .map(|entry| (entry.0 as usize, entry.1 as usize))
-    .collect::<Vec<_>>();
-    return neighbors;
+use crate::common::grid;
+use crate::common::grid::Grid;
+use crate::common::automaton::{GridAutomaton, Neighbors};
+
+// First seat visible in each of the 8 directions, floor is seen through.
+// Floor never changes so this is computed once for the whole game
+fn generate_neighbor_seats(seating: &Grid<char>) -> Grid<Vec<(usize, usize)>> {
+    return seating
+        .first_visible_all(&grid::NEIGHBORS8, |x| *x != '.')
+        .map(|seen| seen.iter().filter_map(|x| *x).collect());
 }
 
-fn generate_neighbor_seats(x: i16, y: i16, seating: &Vec<Vec<char>>) -> Vec<(usize,usize)>{
-    let mut temp_x: i16;
-    let mut temp_y: i16;
-
-    let mut result: Vec<(usize, usize)> = Vec::new();
+// Empty seats with no occupied neighbors fill up, occupied seats with at least
+// tolerance occupied neighbors empty, floor never changes
+fn seat_rule(seat: &char, neighbors: Neighbors<char>, tolerance: usize) -> char {
+    let occupied_count = neighbors.filter(|x| **x == '#').count();
 
-    // get neighbors to the east
-    temp_x = x + 1;
-    temp_y = y;
-    while temp_x >= 0 &&
-          temp_y >= 0 &&
-          temp_x < seating.len() as i16 &&
-          temp_y < seating.first().unwrap().len() as i16 
-    {
-        log::trace!("Looking at {},{}", temp_x, temp_y);
-        if seating[temp_x as usize][temp_y as usize] != '.' {
-            result.push((temp_x as usize, temp_y as usize));
-            break;
-        }
-        temp_x += 1;
-    }
-    // get neighbors to the west
-    temp_x = x - 1;
-    temp_y = y;
-    while temp_x >= 0 &&
-          temp_y >= 0 &&
-          temp_x < seating.len() as i16 &&
-          temp_y < seating.first().unwrap().len() as i16 
-    {
-        log::trace!("Looking at {},{}", temp_x, temp_y);
-        if seating[temp_x as usize][temp_y as usize] != '.' {
-            result.push((temp_x as usize, temp_y as usize));
-            break;
-        }
-        temp_x -= 1;
-    }
-    // get neighbors to the north
-    temp_x = x;
-    temp_y = y - 1;
-    while temp_x >= 0 &&
-          temp_y >= 0 &&
-          temp_x < seating.len() as i16 &&
-          temp_y < seating.first().unwrap().len() as i16 
-    {
-        log::trace!("Looking at {},{}", temp_x, temp_y);
-        if seating[temp_x as usize][temp_y as usize] != '.' {
-            result.push((temp_x as usize, temp_y as usize));
-            break;
-        }
-        temp_y -= 1;
-    }
-    // get neighbors to the south
-    temp_x = x;
-    temp_y = y+1;
-    while temp_x >= 0 &&
-          temp_y >= 0 &&
-          temp_x < seating.len() as i16 &&
-          temp_y < seating.first().unwrap().len() as i16 
-    {
-        log::trace!("Looking at {},{}", temp_x, temp_y);
-        if seating[temp_x as usize][temp_y as usize] != '.' {
-            result.push((temp_x as usize, temp_y as usize));
-            break;
-        }
-        temp_y += 1;
-    }
-    // get neighbors to the north east
-    temp_x = x + 1;
-    temp_y = y - 1;
-    while temp_x >= 0 &&
-          temp_y >= 0 &&
-          temp_x < seating.len() as i16 &&
-          temp_y < seating.first().unwrap().len() as i16 
-    {
-        log::trace!("Looking at {},{}", temp_x, temp_y);
-        if seating[temp_x as usize][temp_y as usize] != '.' {
-            result.push((temp_x as usize, temp_y as usize));
-            break;
-        }
-        temp_x += 1;
-        temp_y -= 1;
-    }
-    // get neighbors to the north west
-    temp_x = x - 1;
-    temp_y = y - 1;
-    while temp_x >= 0 &&
-          temp_y >= 0 &&
-          temp_x < seating.len() as i16 &&
-          temp_y < seating.first().unwrap().len() as i16 
-    {
-        log::trace!("Looking at {},{}", temp_x, temp_y);
-        if seating[temp_x as usize][temp_y as usize] != '.' {
-            result.push((temp_x as usize, temp_y as usize));
-            break;
-        }
-        temp_x -= 1;
-        temp_y -= 1;
-    }
-    // get neighbors to the south east
-    temp_x = x + 1;
-    temp_y = y + 1;
-    while temp_x >= 0 &&
-          temp_y >= 0 &&
-          temp_x < seating.len() as i16 &&
-          temp_y < seating.first().unwrap().len() as i16 
-    {
-        log::trace!("Looking at {},{}", temp_x, temp_y);
-        if seating[temp_x as usize][temp_y as usize] != '.' {
-            result.push((temp_x as usize, temp_y as usize));
-            break;
-        }
-        temp_x += 1;
-        temp_y += 1;
-    }
-    // get neighbors to the south west
-    temp_x = x - 1;
-    temp_y = y + 1;
-    while temp_x >= 0 &&
-          temp_y >= 0 &&
-          temp_x < seating.len() as i16 &&
-          temp_y < seating.first().unwrap().len() as i16 
-    {
-        log::trace!("Looking at {},{}", temp_x, temp_y);
-        if seating[temp_x as usize][temp_y as usize] != '.' {
-            result.push((temp_x as usize, temp_y as usize));
-            break;
-        }
-        temp_x -= 1;
-        temp_y += 1;
-    }
-
-    return result;
-}
-
-fn fill_seats(seating: &Vec<Vec<char>>) -> (i32, Vec<Vec<char>>) {
-    let mut seats_changed = 0;
-    let mut modified_seats = vec![vec!['.'; seating.first().unwrap().len()]; seating.len()];
-
-    for row in 0..seating.len() {
-        for column in 0..seating[row].len() {
-            let seat = seating[row][column];
-            modified_seats[row][column] = seat;
-
-            if seat != '.' {
-                let mut occupied_count = 0;
-                for entry in generate_neighbors(row as i16, column as i16, seating.len() as i16, seating[row].len() as i16) {
-                    if seating[entry.0][entry.1] == '#' {
-                        occupied_count += 1;
-                    }
-                }
-
-                if seat == 'L' && occupied_count == 0 {
-                    modified_seats[row][column] = '#';
-                    seats_changed += 1;
-                    continue;
-                }
-                
-                if seat == '#' && occupied_count >= 4 {
-                    modified_seats[row][column] = 'L';
-                    seats_changed += 1;
-                    continue;
-                }
-            }
-        }
-    }
-    return (seats_changed, modified_seats);
+    return match seat {
+        'L' if occupied_count == 0 => '#',
+        '#' if occupied_count >= tolerance => 'L',
+        _ => *seat
+    };
 }
 
-fn fill_seatsv2(seating: &Vec<Vec<char>>) -> (i32, Vec<Vec<char>>) {
-    let mut seats_changed = 0;
-    let mut modified_seats = vec![vec!['.'; seating.first().unwrap().len()]; seating.len()];
-
-    for row in 0..seating.len() {
-        for column in 0..seating[row].len() {
-            let seat = seating[row][column];
-            modified_seats[row][column] = seat;
-
-            log::trace!("Looking at {} at {},{}", seat, row, column);
-
-            if seat != '.' {
-                let mut occupied_count = 0;
-                for entry in generate_neighbor_seats(row as i16, column as i16, &seating) {
-                    log::trace!("Looking at neighbor {},{}", entry.0, entry.1);
-                    if seating[entry.0][entry.1] == '#' {
-                        occupied_count += 1;
-                    }
-                }
-
-                if seat == 'L' && occupied_count == 0 {
-                    modified_seats[row][column] = '#';
-                    seats_changed += 1;
-                    continue;
-                }
-                
-                if seat == '#' && occupied_count >= 5 {
-                    modified_seats[row][column] = 'L';
-                    seats_changed += 1;
-                    continue;
-                }
-            }
-        }
-    }
-    return (seats_changed, modified_seats);
-}
-
-fn part1(seating: &Vec<Vec<char>>) -> i32 {
+fn part1(seating: &Grid<char>) -> i32 {
     log::info!("Running Part 1");
-
-    let mut seats_changed = -1;
-    let mut modified_seating = seating.clone();
-
-    while seats_changed != 0 {
-        let result = fill_seats(&modified_seating);
-        seats_changed = result.0;
-        modified_seating = result.1;
-    }
-
-    let mut occupied_seats = 0;
-    for row in 0..modified_seating.len() {
-        for column in 0..modified_seating[row].len() { 
-            if modified_seating[row][column] == '#' {
-                occupied_seats += 1;
-            }
-        }
-    }
+    let mut automaton = GridAutomaton::with_neighborhood(
+        seating.clone(),
+        |grid, x, y| grid.neighbors8(x, y).collect(),
+        |seat, neighbors| seat_rule(seat, neighbors, 4));
+    let generations = automaton.run_until_stable();
+    log::debug!("Stable after {} generations", generations);
+
+    let occupied_seats = automaton.count(|x| *x == '#') as i32;
     log::info!("Steady state seats occupied are {}", occupied_seats);
     return occupied_seats;
 }
 
-fn part2(seating: &Vec<Vec<char>>) -> i32 {
+fn part2(seating: &Grid<char>) -> i32 {
     log::info!("Running Part 2");
-    let mut seats_changed = -1;
-    let mut modified_seating = seating.clone();
+    let mut automaton = GridAutomaton::new(
+        seating.clone(),
+        generate_neighbor_seats(seating),
+        |seat, neighbors| seat_rule(seat, neighbors, 5));
 
-    while seats_changed != 0 {
-        let result = fill_seatsv2(&modified_seating);
-        seats_changed = result.0;
-        modified_seating = result.1;
-
-        log::debug!("Printing state");
-        for line in modified_seating.clone() {
-            log::debug!("{:?}", line);
-        }
+    while automaton.step() != 0 {
+        log::debug!("Printing state after generation {}\n{}", automaton.generation(), automaton.grid());
     }
 
-    let mut occupied_seats = 0;
-    for row in 0..modified_seating.len() {
-        for column in 0..modified_seating[row].len() { 
-            if modified_seating[row][column] == '#' {
-                occupied_seats += 1;
-            }
-        }
-    }
+    let occupied_seats = automaton.count(|x| *x == '#') as i32;
     log::info!("Steady state seats occupied are {}", occupied_seats);
     return occupied_seats;
 }
 
-pub fn run(filename: impl AsRef<Path>) {
-    let lines = io::lines_from_file(filename).iter().map(|x| x.chars().collect()).collect();
+pub fn run(source: &io::InputSource) {
+    let input = io::Input::from_source(source);
+    let seating = Grid::parse(&input.line_vec()).unwrap();
     let now = Instant::now();
-    part1(&lines);
+    part1(&seating);
     log::info!("Part 1: {}ms", now.elapsed().as_millis());
-    part2(&lines);
+    part2(&seating);
     log::info!("Part 1 + 2: {}ms", now.elapsed().as_millis());
 }
 
@@ -292,7 +71,7 @@ mod tests {
 
     #[test]
     fn part1_test() {
-        let seat_map: Vec<Vec<char>> = vec![
+        let seat_map = Grid::parse(&[
             "L.LL.LL.LL",
             "LLLLLLL.LL",
             "L.L.L..L..",
@@ -303,14 +82,14 @@ mod tests {
             "LLLLLLLLLL",
             "L.LLLLLL.L",
             "L.LLLLL.LL"
-        ].iter().map(|x| x.chars().collect()).collect();
+        ]).unwrap();
 
         assert_eq!(37, part1(&seat_map))
     }
 
     #[test]
     fn part2_test() {
-        let seat_map: Vec<Vec<char>> = vec![
+        let seat_map = Grid::parse(&[
             "L.LL.LL.LL",
             "LLLLLLL.LL",
             "L.L.L..L..",
@@ -321,56 +100,59 @@ mod tests {
             "LLLLLLLLLL",
             "L.LLLLLL.L",
             "L.LLLLL.LL"
-        ].iter().map(|x| x.chars().collect()).collect();
+        ]).unwrap();
 
         assert_eq!(26, part2(&seat_map))
     }
 
     #[test]
     fn generate_neighbor_seats_test() {
-        let all_seat_map: Vec<Vec<char>> = vec![
-            vec!['L','L','L'],
-            vec!['L','L','L'],
-            vec!['L','L','L'],
-        ];
-        assert_eq!(3, generate_neighbor_seats(0, 0, &all_seat_map).len());
-        assert_eq!(5, generate_neighbor_seats(0, 1, &all_seat_map).len());
-        assert_eq!(3, generate_neighbor_seats(0, 2, &all_seat_map).len());
-        assert_eq!(5, generate_neighbor_seats(1, 0, &all_seat_map).len());
-        assert_eq!(8, generate_neighbor_seats(1, 1, &all_seat_map).len());
-        assert_eq!(5, generate_neighbor_seats(1, 2, &all_seat_map).len());
-        assert_eq!(3, generate_neighbor_seats(2, 0, &all_seat_map).len());
-        assert_eq!(5, generate_neighbor_seats(2, 1, &all_seat_map).len());
-        assert_eq!(3, generate_neighbor_seats(2, 2, &all_seat_map).len());
-
-        let no_seat_map: Vec<Vec<char>> = vec![
-            vec!['.','.','.'],
-            vec!['.','.','.'],
-            vec!['.','.','.'],
-        ];
-        assert_eq!(0, generate_neighbor_seats(0, 0, &no_seat_map).len());
-        assert_eq!(0, generate_neighbor_seats(0, 1, &no_seat_map).len());
-        assert_eq!(0, generate_neighbor_seats(0, 2, &no_seat_map).len());
-        assert_eq!(0, generate_neighbor_seats(1, 0, &no_seat_map).len());
-        assert_eq!(0, generate_neighbor_seats(1, 1, &no_seat_map).len());
-        assert_eq!(0, generate_neighbor_seats(1, 2, &no_seat_map).len());
-        assert_eq!(0, generate_neighbor_seats(2, 0, &no_seat_map).len());
-        assert_eq!(0, generate_neighbor_seats(2, 1, &no_seat_map).len());
-        assert_eq!(0, generate_neighbor_seats(2, 2, &no_seat_map).len());
-
-        let some_seat_map: Vec<Vec<char>> = vec![
-            vec!['.','.','L'],
-            vec!['.','L','.'],
-            vec!['L','.','.'],
-        ];
-        assert_eq!(3, generate_neighbor_seats(0, 0, &some_seat_map).len());
-        assert_eq!(2, generate_neighbor_seats(0, 1, &some_seat_map).len());
-        assert_eq!(1, generate_neighbor_seats(0, 2, &some_seat_map).len());
-        assert_eq!(2, generate_neighbor_seats(1, 0, &some_seat_map).len());
-        assert_eq!(2, generate_neighbor_seats(1, 1, &some_seat_map).len());
-        assert_eq!(2, generate_neighbor_seats(1, 2, &some_seat_map).len());
-        assert_eq!(1, generate_neighbor_seats(2, 0, &some_seat_map).len());
-        assert_eq!(2, generate_neighbor_seats(2, 1, &some_seat_map).len());
-        assert_eq!(3, generate_neighbor_seats(2, 2, &some_seat_map).len());
+        let all_seat_map = Grid::parse(&[
+            "LLL",
+            "LLL",
+            "LLL",
+        ]).unwrap();
+        let all_seats = generate_neighbor_seats(&all_seat_map);
+        assert_eq!(3, all_seats[(0, 0)].len());
+        assert_eq!(5, all_seats[(0, 1)].len());
+        assert_eq!(3, all_seats[(0, 2)].len());
+        assert_eq!(5, all_seats[(1, 0)].len());
+        assert_eq!(8, all_seats[(1, 1)].len());
+        assert_eq!(5, all_seats[(1, 2)].len());
+        assert_eq!(3, all_seats[(2, 0)].len());
+        assert_eq!(5, all_seats[(2, 1)].len());
+        assert_eq!(3, all_seats[(2, 2)].len());
+
+        let no_seat_map = Grid::parse(&[
+            "...",
+            "...",
+            "...",
+        ]).unwrap();
+        let no_seats = generate_neighbor_seats(&no_seat_map);
+        assert_eq!(0, no_seats[(0, 0)].len());
+        assert_eq!(0, no_seats[(0, 1)].len());
+        assert_eq!(0, no_seats[(0, 2)].len());
+        assert_eq!(0, no_seats[(1, 0)].len());
+        assert_eq!(0, no_seats[(1, 1)].len());
+        assert_eq!(0, no_seats[(1, 2)].len());
+        assert_eq!(0, no_seats[(2, 0)].len());
+        assert_eq!(0, no_seats[(2, 1)].len());
+        assert_eq!(0, no_seats[(2, 2)].len());
+
+        let some_seat_map = Grid::parse(&[
+            "..L",
+            ".L.",
+            "L..",
+        ]).unwrap();
+        let some_seats = generate_neighbor_seats(&some_seat_map);
+        assert_eq!(3, some_seats[(0, 0)].len());
+        assert_eq!(2, some_seats[(0, 1)].len());
+        assert_eq!(1, some_seats[(0, 2)].len());
+        assert_eq!(2, some_seats[(1, 0)].len());
+        assert_eq!(2, some_seats[(1, 1)].len());
+        assert_eq!(2, some_seats[(1, 2)].len());
+        assert_eq!(1, some_seats[(2, 0)].len());
+        assert_eq!(2, some_seats[(2, 1)].len());
+        assert_eq!(3, some_seats[(2, 2)].len());
     }
 }
\ No newline at end of file
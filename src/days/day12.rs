@@ -1,125 +1,70 @@
-use std::path::Path as Path;
 use std::time::Instant;
 
 use crate::common::io;
+use crate::common::geom::{Dir, Point2};
+use crate::common::parse;
 
-fn move_ship(x: f32, y: f32, m: f32, operator: char, value: f32) -> (f32, f32, f32) {
-    let mut new_x = x;
-    let mut new_y = y;
-    let mut new_m = m;
-
-    match operator {
-        'E' => {
-            new_x += value.abs();
-        },
-        'W' => {
-            new_x -= value.abs();
-        },
-        'N' => {
-            new_y += value.abs();
-        },
-        'S' => {
-            new_y -= value.abs();
-        },
-        'L' => {
-            new_m += value.abs();
-        },
-        'R' => {
-            new_m -= value.abs();
-        },
-        'F' => {
-            new_x += value.abs() * (m * 0.0174533).cos();
-            new_y += value.abs() * (m * 0.0174533).sin();
-        },
-        _ => {}
-    }
-    return (new_x, new_y, new_m);
+type Point = Point2<i64>;
+
+// "F10" into the action and its value
+fn parse_instructions(lines: &[String]) -> Vec<(char, i64)> {
+    let action = parse::satisfy(|c| "NESWLRF".contains(c), "one of NESWLRF");
+    parse::parse_lines(parse::pair(action, parse::integer::<i64>()), lines).unwrap()
 }
 
-fn use_waypoint_navigation(x: f32, y: f32, way_x: f32, way_y: f32, operator: char, value: f32) -> (f32, f32, f32, f32) {
-    let mut new_x = x;
-    let mut new_y = y;
-    let mut new_way_x = way_x;
-    let mut new_way_y = way_y;
-
-    match operator {
-        'E' => {
-            new_way_x += value.abs();
-        },
-        'W' => {
-            new_way_x -= value.abs();
-        },
-        'N' => {
-            new_way_y += value.abs();
-        },
-        'S' => {
-            new_way_y -= value.abs();
-        },
-        'L' => {
-            new_way_x = (way_x * (value.abs() * 0.0174533).cos()) - (way_y * (value.abs() * 0.0174533).sin());
-            new_way_y = (way_x * (value.abs() * 0.0174533).sin()) + (way_y * (value.abs() * 0.0174533).cos());
-        },
-        'R' => {
-            new_way_x = (way_x * ( -1.0 * value.abs() * 0.0174533).cos()) - (way_y * (-1.0 * value.abs() * 0.0174533).sin());
-            new_way_y = (way_x * ( -1.0 * value.abs() * 0.0174533).sin()) + (way_y * ( -1.0 * value.abs() * 0.0174533).cos());
-        },
-        'F' => {
-            new_x += value.abs() * way_x;
-            new_y += value.abs() * way_y;
-        },
-        _ => {}
-    }
-    return (new_x, new_y, new_way_x, new_way_y);
+// N/E/S/W move the ship, L/R turn it and F moves it along its heading
+fn move_ship(ship: Point, heading: Dir, operator: char, value: i64) -> (Point, Dir) {
+    return match operator {
+        'L' => (ship, heading.turn(value)),
+        'R' => (ship, heading.turn(-value)),
+        'F' => (ship + heading.offset() * value, heading),
+        _ => (ship + Dir::from_char(operator).unwrap().offset() * value, heading)
+    };
+}
+
+// N/E/S/W move the waypoint, L/R rotate it about the ship and F moves the ship
+// towards the waypoint value times
+fn use_waypoint_navigation(ship: Point, waypoint: Point, operator: char, value: i64) -> (Point, Point) {
+    return match operator {
+        'L' => (ship, waypoint.rotate(value)),
+        'R' => (ship, waypoint.rotate(-value)),
+        'F' => (ship + waypoint * value, waypoint),
+        _ => (ship, waypoint + Dir::from_char(operator).unwrap().offset() * value)
+    };
 }
 
-fn part1(lines: &Vec<String>) -> f32 {
+fn part1(lines: &Vec<String>) -> i64 {
     log::info!("Running Part 1");
-    let mut x: f32 = 0.0;
-    let mut y: f32 = 0.0;
-    let mut m: f32 = 0.0;
-
-    for line in lines {
-        let chars: Vec<char> = line.chars().collect();
-        let operator  = chars[0];
-        let operrand: String = chars.into_iter().skip(1).collect();
-        let value: i32 = operrand.parse().unwrap();
-        log::debug!("Parsed operator {} and value {}", operator, value);
-        let result = move_ship(x, y, m, operator, value as f32);
-        x = result.0;
-        y = result.1;
-        m = result.2;
-        log::debug!("New x {} y {} heading {}", x, y, m);
+    let mut ship = Point::origin();
+    let mut heading = Dir::East;
+
+    for (operator, value) in parse_instructions(lines) {
+        let result = move_ship(ship, heading, operator, value);
+        ship = result.0;
+        heading = result.1;
+        log::debug!("New position {} heading {:?}", ship, heading);
     }
-    log::info!("manhatten distance travelled is {}", x.abs().round() + y.abs().round());
-    return x.abs().round() + y.abs().round();
+    log::info!("manhatten distance travelled is {}", ship.manhattan());
+    return ship.manhattan();
 }
 
-fn part2(lines: &Vec<String>) -> f32 {
+fn part2(lines: &Vec<String>) -> i64 {
     log::info!("Running Part 2");
-    let mut waypoint_x: f32 = 10.0;
-    let mut waypoint_y: f32 = 1.0;
-    let mut ship_x: f32 = 0.0;
-    let mut ship_y: f32 = 0.0;
-
-    for line in lines {
-        let chars: Vec<char> = line.chars().collect();
-        let operator  = chars[0];
-        let operrand: String = chars.into_iter().skip(1).collect();
-        let value: i32 = operrand.parse().unwrap();
-        log::debug!("Parsed operator {} and value {}", operator, value);
-        let result = use_waypoint_navigation(ship_x, ship_y, waypoint_x, waypoint_y, operator, value as f32);
-        ship_x = result.0;
-        ship_y = result.1;
-        waypoint_x = result.2;
-        waypoint_y = result.3;
-        log::debug!("Ship x {} y {} Waypoint x {} y {}", ship_x, ship_y, waypoint_x, waypoint_y);
+    let mut waypoint = Point::new(10, 1);
+    let mut ship = Point::origin();
+
+    for (operator, value) in parse_instructions(lines) {
+        let result = use_waypoint_navigation(ship, waypoint, operator, value);
+        ship = result.0;
+        waypoint = result.1;
+        log::debug!("Ship {} Waypoint {}", ship, waypoint);
     }
-    log::info!("manhatten distance travelled is {}", ship_x.abs().round() + ship_y.abs().round());
-    return ship_x.abs().round() + ship_y.abs().round();
+    log::info!("manhatten distance travelled is {}", ship.manhattan());
+    return ship.manhattan();
 }
 
-pub fn run(filename: impl AsRef<Path>) {
-    let lines = io::lines_from_file(filename);
+pub fn run(source: &io::InputSource) {
+    let lines = source.lines();
     let now = Instant::now();
     part1(&lines);
     log::info!("Part 1: {}ms", now.elapsed().as_millis());
@@ -141,7 +86,7 @@ mod tests {
             "F11"
         ].iter().map(|x| x.to_string()).collect();
 
-        assert_eq!(25.0, part1(&instructions));
+        assert_eq!(25, part1(&instructions));
     }
 
     #[test]
@@ -154,6 +99,15 @@ mod tests {
             "F11"
         ].iter().map(|x| x.to_string()).collect();
 
-        assert_eq!(286.0, part2(&instructions));
+        assert_eq!(286, part2(&instructions));
+    }
+
+    #[test]
+    fn use_waypoint_navigation_test() {
+        let ship = Point::new(170, 38);
+        let waypoint = Point::new(10, 4);
+        assert_eq!((ship, Point::new(4, -10)), use_waypoint_navigation(ship, waypoint, 'R', 90));
+        assert_eq!((ship, Point::new(-10, -4)), use_waypoint_navigation(ship, waypoint, 'L', 180));
+        assert_eq!((Point::new(214, -72), Point::new(4, -10)), use_waypoint_navigation(ship, Point::new(4, -10), 'F', 11));
     }
 }
\ No newline at end of file
@@ -1,8 +1,8 @@
-use std::path::Path as Path;
 use std::time::Instant;
-use num::integer;
 
 use crate::common::io;
+use crate::common::convertor;
+use crate::common::math;
 
 fn part1(timestamp: i32, busses: &Vec<i32>) -> f32 {
     log::info!("Running Part 1");
@@ -21,83 +21,40 @@ fn part1(timestamp: i32, busses: &Vec<i32>) -> f32 {
     return earliest_bus as f32 * min_arrival_time as f32;
 }
 
-fn part2(busses_list: &Vec<&str>) -> i128 {
+// Bus i leaving i minutes after the timestamp means timestamp = -i (mod bus),
+// solve all of those at once with the chinese remainder theorem
+fn part2(busses_list: &[&str]) -> Option<i128> {
     log::info!("Running Part 2");
-    let in_service_busses: Vec<i32> = busses_list
-                            .iter()
-                            .filter(|x| x.to_string() != "x")
-                            .map(|x| x.parse::<i32>().unwrap())
-                            .collect();
-    let mut product: i64 = 1;
-    for bus in in_service_busses {
-        product *= bus as i64;
-    }
-    
-    log::debug!("LCM {}", product);
-
-    let mut busses: Vec<(i32, i32)> = Vec::new();
+    let mut busses: Vec<(i128, i128)> = Vec::new();
 
     for i in 0..busses_list.len() {
         if busses_list[i] != "x" {
-            let number: i32 = busses_list[i].parse().unwrap();
-            let index = i as i32;
+            let number: i128 = busses_list[i].parse().unwrap();
+            let index = i as i128;
             log::debug!("(x+{}) mod {} = 0", index, number);
-            busses.push((number, index));
+            busses.push((-index, number));
         }
     }
 
-    busses.sort_by(|a, b| a.0.partial_cmp(&b.0).unwrap());
-    busses.reverse();
-    log::debug!("Sorted list {:?}", busses);
-
-    // If we were to approach this by adding one bus to the mix at a time,
-    // the recurrence period should by the LCM of busses ids
-    let mut count = 0;
-    let mut occurance: i128 = 0;
-    let mut period: i128 = 1;
-
-    while count < busses.len() {
-        while (occurance + busses[count].1 as i128) % busses[count].0 as i128 != 0 {
-            occurance += period;
-        }
-        period *= busses[count].0 as i128;
-        log::debug!("First occurance satisfying {} rules is {}, period {}", count+1, occurance, period);
-        count += 1;
+    let solution = math::crt(&busses);
+    match solution {
+        Some((occurance, period)) => log::info!("First occurance satisfying all rules is {}, repeating every {}", occurance, period),
+        None => log::info!("No timestamp satisfies all rules")
     }
-    log::info!("First occurance satisfying all rules is {}", occurance);
-    return occurance;
+    solution.map(|(occurance, _)| occurance)
 }
 
-fn is_set_coprime(numbers: &Vec<i32>) -> bool {
-    for number in numbers {
-        for other in numbers {
-            if number != other && integer::gcd(*number, *other) != 1 {
-                return false;
-            }
-        }
-    }
-    return true;
-}
-
-pub fn run(filename: impl AsRef<Path>) {
-    let lines: Vec<String> = io::lines_from_file(filename);
+pub fn run(source: &io::InputSource) {
+    let lines: Vec<String> = source.lines();
     let timestamp: i32 = lines[0].parse().unwrap();
     let busses_list: Vec<&str> = lines[1].split(',').collect();
-    let in_service_busses = busses_list
-                            .iter()
-                            .filter(|x| x.to_string() != "x")
-                            .map(|x| x.parse::<i32>().unwrap())
-                            .collect();
+    // "x" marks a bus that is out of service
+    let in_service_busses: Vec<i32> = convertor::parse_fields(2, busses_list.iter().copied().filter(|x| *x != "x")).unwrap();
     let now = Instant::now();
     part1(timestamp, &in_service_busses);
     log::info!("Part 1: {}us", now.elapsed().as_micros());
-    
-    if is_set_coprime(&in_service_busses) {
-        part2(&busses_list);
-        log::info!("Part 1 + 2: {}us", now.elapsed().as_micros());
-    } else {
-        panic!("If the bus set is not coprime we cannot solve this, crash and burn");
-    }
+    part2(&busses_list);
+    log::info!("Part 1 + 2: {}us", now.elapsed().as_micros());
 }
 
 #[cfg(test)]
@@ -113,6 +70,14 @@ mod tests {
     #[test]
     fn part2_test() {
         let busses: Vec<&str> = vec!["7","13","x","x","59","x","31","19"];
-        assert_eq!(1068781, part2(&busses));
+        assert_eq!(Some(1068781), part2(&busses));
+        assert_eq!(Some(3417), part2(&["17","x","13","19"]));
+        assert_eq!(Some(1202161486), part2(&["1789","37","47","1889"]));
+    }
+
+    #[test]
+    fn part2_not_coprime_test() {
+        assert_eq!(Some(4), part2(&["4","x","6"]));
+        assert_eq!(None, part2(&["4","6"]));
     }
 }
\ No newline at end of file
@@ -1,154 +1,98 @@
-use std::path::Path as Path;
 use std::time::Instant;
-use std::collections::HashSet;
-use std::collections::VecDeque;
+use std::collections::HashMap;
 use crate::common::io;
+use crate::common::bits::Mask;
+use crate::common::parse;
+use crate::common::parse::{Cursor, Step, literal, map, pair, preceded};
 
-fn set_values_of_memory_and_sum(instructions: &Vec<String>) -> i128 {
-    let size = (2 as i64).pow(36);
-    let mut memory: Vec<i64> = vec![0;  size as usize];
-    let mut mask_1: i64 = 0;
-    let mut mask_0: i64 = 0;
-    let mut set: HashSet<usize> = HashSet::new();
-    let mut sum: i128 = 0;
+enum Instruction {
+    Mask(Mask),
+    Mem(u64, i64)
+}
 
-    for instruction in instructions {
-        log::debug!("Processing instruction {}", instruction);
-        let mut modified_instruction = instruction.replace("mask = ", "mask;");
-        modified_instruction = modified_instruction.replace("mem[", "mem;");
-        modified_instruction = modified_instruction.replace("] = ", ";");
+// "mask = X1X0" or "mem[8] = 11"
+fn instruction_parser<'a>() -> impl Fn(Cursor<'a>) -> Step<'a, Instruction> {
+    let mask = preceded(literal("mask = "), parse::try_map(
+        parse::take_while1(|c| c == 'X' || c == '0' || c == '1', "mask bits"),
+        |bits: &str| bits.parse().map(Instruction::Mask).map_err(|_| "a mask of at most 64 bits")));
+    let mem = map(
+        pair(preceded(literal("mem["), parse::integer::<u64>()), preceded(literal("] = "), parse::integer::<i64>())),
+        |(address, value)| Instruction::Mem(address, value));
+    parse::alt(mask, mem)
+}
+
+fn parse_program(lines: &[String]) -> Vec<Instruction> {
+    parse::parse_lines(instruction_parser(), lines).unwrap()
+}
 
-        let tokens: Vec<&str> = modified_instruction.split(';').collect();
-        let command = tokens[0];
+// Only a few hundred of the 2^36 addresses are ever written, keep just those
+fn sum_of_memory(memory: &HashMap<u64, i64>) -> i128 {
+    memory.values().map(|x| *x as i128).sum()
+}
 
-        match command {
-            "mem" => {
-                let index: usize = tokens[1].parse().unwrap();
-                let value: i64 = tokens[2].parse().unwrap();
+// The mask overwrites bits of every value written
+fn set_values_of_memory_and_sum(instructions: &Vec<Instruction>) -> i128 {
+    let mut memory: HashMap<u64, i64> = HashMap::new();
+    let mut mask = Mask::default();
 
-                if set.contains(&index) {
-                    sum -= memory[index] as i128;
-                }
-                set.insert(index);
-                memory[index] = (value & mask_0) | mask_1;
-                sum += memory[index] as i128;
-                log::debug!("Setting memory location {} with {}, original value {}", index, memory[index], value);
-            },
-            "mask" => {
-                let bin_mask_1 = tokens[1].replace("X", "0");
-                let bin_mask_0 = tokens[1].replace("X", "1");
-                mask_1 = i64::from_str_radix(&bin_mask_1, 2).unwrap();
-                mask_0 = i64::from_str_radix(&bin_mask_0, 2).unwrap();
-                log::debug!("Setting mask to {}, mask_0 {} mask_1 {}", tokens[1], mask_0, mask_1);
+    for instruction in instructions {
+        match instruction {
+            Instruction::Mem(index, value) => {
+                let masked = mask.apply(*value as u64) as i64;
+                memory.insert(*index, masked);
+                log::debug!("Setting memory location {} with {}, original value {}", index, masked, value);
             },
-            _ => {
-                panic!("Instruction not known, something is messed up");
+            Instruction::Mask(bits) => {
+                mask = *bits;
+                log::debug!("Setting mask to {:?}", mask);
             }
         }
     }
 
-    return sum;
-}
-
-fn generate_addresses(mask: &String, address: &String) -> Vec<usize> {
-    log::trace!("Mask {} adddress {}", mask, address);
-    let mut alternatives: VecDeque<(Vec<char>, usize)> = VecDeque::new();
-    let mut addresses: Vec<usize> = Vec::new();
-    let mut address_chars: Vec<char> = vec!['0'; mask.len() - address.len()];
-    address_chars.append(&mut address.chars().collect());
-    let mask_chars: Vec<char> = mask.chars().collect();
-    
-    alternatives.push_back((mask_chars.clone(), 0));
-
-    while ! alternatives.is_empty() {
-        let entry = alternatives.pop_front().unwrap();
-        log::trace!("Entry {:?} index {}", entry.0, entry.1);
-        if entry.0.len() == entry.1 {
-            let complete_address_string = entry.0.into_iter().collect::<String>();
-            let complete_address = usize::from_str_radix(&complete_address_string, 2).unwrap();
-            log::trace!("Adding adddress {}", complete_address);
-            addresses.push(complete_address);
-        } else {
-            if entry.0[entry.1] == 'X' {
-                let mut clone_1 = entry.0.clone();
-                clone_1[entry.1] = '1';
-                let mut clone_0 = entry.0.clone();
-                clone_0[entry.1] = '0';
-                alternatives.push_back((clone_1, entry.1 + 1));
-                alternatives.push_back((clone_0, entry.1 + 1));
-            } else {
-                let mut clone = entry.0.clone();
-                if entry.1 < address_chars.len() && address_chars[entry.1] == '1' {
-                    clone[entry.1] = '1';
-                } 
-                alternatives.push_back((clone, entry.1 + 1));
-            }
-        }
-    }
-    log::debug!("Generated {} mirror addresses", addresses.len());
-    return addresses;
+    sum_of_memory(&memory)
 }
 
-fn set_values_of_memory_and_sumv2(instructions: &Vec<String>) -> i128 {
-    let size = (2 as i64).pow(36);
-    let mut memory: Vec<i64> = vec![0;  size as usize];
-    let mut mask: String = "".to_string();
-    let mut set: HashSet<usize> = HashSet::new();
-    let mut sum: i128 = 0;
+// The mask overwrites bits of the address instead, floating bits take every
+// value so each write lands on 2^floating addresses
+fn set_values_of_memory_and_sumv2(instructions: &Vec<Instruction>) -> i128 {
+    let mut memory: HashMap<u64, i64> = HashMap::new();
+    let mut mask = Mask::default();
 
     for instruction in instructions {
-        log::debug!("Processing instruction {}", instruction);
-        let mut modified_instruction = instruction.replace("mask = ", "mask;");
-        modified_instruction = modified_instruction.replace("mem[", "mem;");
-        modified_instruction = modified_instruction.replace("] = ", ";");
-
-        let tokens: Vec<&str> = modified_instruction.split(';').collect();
-        let command = tokens[0];
-
-        match command {
-            "mem" => {
-                let address: String = format!("{:b}", tokens[1].parse::<usize>().unwrap());
-                let value: i64 = tokens[2].parse().unwrap();
-
-                for memory_address in generate_addresses(&mask, &address) {
-                    log::debug!("Setting memory location {} with {}", memory_address, value);
-                    if set.contains(&memory_address) {
-                        sum -= memory[memory_address] as i128;
-                    }
-                    set.insert(memory_address);
-                    memory[memory_address] = value;
-                    sum += memory[memory_address] as i128;
+        match instruction {
+            Instruction::Mem(address, value) => {
+                log::debug!("Writing {} to {} mirror addresses", value, 1u64 << mask.floating.count_ones());
+                for memory_address in mask.float(*address) {
+                    log::trace!("Setting memory location {} with {}", memory_address, value);
+                    memory.insert(memory_address, *value);
                 }
             },
-            "mask" => {
-                mask = tokens[1].to_string();
-                log::debug!("Setting mask to {}", mask);
-            },
-            _ => {
-                panic!("Instruction not known, something is messed up");
+            Instruction::Mask(bits) => {
+                mask = *bits;
+                log::debug!("Setting mask to {:?}", mask);
             }
         }
     }
 
-    return sum;
+    sum_of_memory(&memory)
 }
 
 fn part1(lines: &Vec<String>) -> i128 {
     log::info!("Running Part 1");
-    let sum = set_values_of_memory_and_sum(&lines);
+    let sum = set_values_of_memory_and_sum(&parse_program(lines));
     log::info!("Found the sum to be {}", sum);
     return sum;
 }
 
 fn part2(lines: &Vec<String>) -> i128 {
     log::info!("Running Part 2");
-    let sum = set_values_of_memory_and_sumv2(&lines);
+    let sum = set_values_of_memory_and_sumv2(&parse_program(lines));
     log::info!("Found the sum to be {}", sum);
     return sum;
 }
 
-pub fn run(filename: impl AsRef<Path>) {
-    let lines = io::lines_from_file(filename);
+pub fn run(source: &io::InputSource) {
+    let lines = source.lines();
     let now = Instant::now();
     part1(&lines);
     log::info!("Part 1: {}ms", now.elapsed().as_millis());
@@ -160,6 +104,18 @@ pub fn run(filename: impl AsRef<Path>) {
 mod tests {
     use super::*;
 
+    #[test]
+    fn parse_program_test() {
+        let lines: Vec<String> = ["mask = X1X0", "mem[8] = 11"].iter().map(|x| x.to_string()).collect();
+        let program = parse_program(&lines);
+        assert!(matches!(&program[0], Instruction::Mask(bits) if bits.floating == 0b1010 && bits.ones == 0b0100));
+        assert!(matches!(program[1], Instruction::Mem(8, 11)));
+
+        let too_wide = format!("mask = {}", "X".repeat(65));
+        let failure = parse::parse_all(instruction_parser(), &too_wide).err().unwrap();
+        assert_eq!("expected a mask of at most 64 bits at column 8", failure.to_string());
+    }
+
     #[test]
     fn part1_test() {
         let instructions: Vec<String> = vec![
@@ -1,7 +1,7 @@
-use std::path::Path as Path;
 use std::time::Instant;
 use std::collections::HashMap;
 use crate::common::io;
+use crate::common::convertor;
 
 fn predict_nth_number(start_list: &Vec<i64>, nth_number: i64) -> i64 {
     let mut memory: HashMap<i64, i64> = HashMap::new();
@@ -47,13 +47,8 @@ fn part2(start_list: &Vec<i64>) -> i64 {
     return last_spoken;
 }
 
-pub fn run(filename: impl AsRef<Path>) {
-    let numbers: Vec<i64> = 
-    io::lines_from_file(filename)
-    .first()
-    .unwrap()
-    .split(',')
-    .map(|x| x.parse().unwrap()).collect();
+pub fn run(source: &io::InputSource) {
+    let numbers: Vec<i64> = convertor::parse_comma_separated(1, source.lines().first().unwrap()).unwrap();
     let now = Instant::now();
     part1(&numbers);
     log::info!("Part 1: {}ms", now.elapsed().as_millis());
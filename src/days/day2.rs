@@ -1,68 +1,70 @@
 use std::time::Instant;
-use std::path::Path as Path;
 
 use crate::common::io;
+use crate::common::interval::Interval;
+use crate::common::parse;
+use crate::common::parse::{Cursor, Step, literal, map, pair, terminated};
 
+// For part 1 range bounds how often character appears, for part 2 its ends
+// are the two 1 based positions to check
+#[derive(Debug)]
 struct PasswordRule {
-    min: i8,
-    max: i8,
+    range: Interval<i8>,
     character: char
 }
 
 type PasswordEntry = (String, PasswordRule);
 
-// Parse a line to extract PasswordRule or fail
-fn parse_rule_string(rule_string: &str) -> Result<PasswordRule, &'static str> {
-    let tokens: Vec<&str> = rule_string.split_whitespace().collect();
-    let range: Vec<&str> = tokens[0].trim().split('-').collect();
-    let min: i8 = range[0].trim().parse().unwrap();
-    let max: i8 = range[1].trim().parse().unwrap();
-    let character: char = tokens[1].trim().chars().next().unwrap();
-    log::debug!("input was {}, rule generated min {}, max {}, char {}", rule_string, min, max, character);
-    return Ok(PasswordRule {min: min, max: max, character: character});
+// "1-3 a" into the range 1-3 and character a
+fn rule_parser<'a>() -> impl Fn(Cursor<'a>) -> Step<'a, PasswordRule> {
+    let rule = parse::triple(
+        parse::integer::<i8>(),
+        parse::preceded(literal("-"), parse::integer::<i8>()),
+        parse::preceded(parse::spaces(), parse::character()));
+    map(rule, |(min, max, character)| PasswordRule {range: Interval::new(min, max), character})
 }
 
-// Generate PasswordEntry for each line
+// Generate PasswordEntry for each line, a line like "1-3 a: abcde" is a rule
+// followed by the password. Lines that don't parse are reported and skipped
 fn parse_file(lines: &Vec<String>) -> Vec<PasswordEntry> {
+    let entry = map(
+        pair(terminated(rule_parser(), literal(": ")), parse::take_while1(|c| !c.is_whitespace(), "a password")),
+        |(rule, password)| (password.to_string(), rule));
     let mut entries: Vec<PasswordEntry> = Vec::new();
-    
+
     for line in lines {
-        let parts: Vec<&str> = line.split(':').collect();
-        let rule_str = parts[0].trim();
-        let password = parts[1].trim().to_string();
-        log::debug!("input was {}, Found password {} and rule_str {}", line, password, rule_str);
-
-        let rule = parse_rule_string(rule_str);
-        if rule.is_ok() {
-            entries.push((password, rule.unwrap()))
+        match parse::parse_all(&entry, line.trim()) {
+            Ok(parsed) => {
+                log::debug!("input was {}, Found password {} and rule {} {}", line, parsed.0, parsed.1.range, parsed.1.character);
+                entries.push(parsed);
+            },
+            Err(failure) => log::error!("Skipping {}: {}", line, failure)
         }
     }
 
     return entries;
 }
 
-// Check that the count of given character is greater than equal to min value
-// Check that the count of given chatacter is less than equal to max value
+// Check that the count of given character falls within the rule's range
 fn check_type1_validity(entry: &PasswordEntry) -> bool{
     let occurences: Vec<char> = entry.0.chars().filter(|x| *x == entry.1.character).collect();
     let count = occurences.len() as i8;
     log::debug!(
-        "Checking rule for {}, looking for character {} found count {}, expected min {}, max {}",
+        "Checking rule for {}, looking for character {} found count {}, expected {}",
         entry.0,
         entry.1.character,
         count,
-        entry.1.min,
-        entry.1.max);
-    return count >= entry.1.min && count <= entry.1.max;
+        entry.1.range);
+    return entry.1.range.contains(count);
 }
 
 // Check that the character at nth index obeys a rule, and only 1 of two rules are valid
 fn check_type2_validity(entry: &PasswordEntry) -> bool{
     let mut count = 0;
-    if entry.0.chars().nth((entry.1.min - 1) as usize).unwrap() == entry.1.character {
+    if entry.0.chars().nth((entry.1.range.start - 1) as usize).unwrap() == entry.1.character {
         count+= 1;
     }
-    if entry.0.chars().nth((entry.1.max - 1) as usize).unwrap() == entry.1.character {
+    if entry.0.chars().nth((entry.1.range.end - 1) as usize).unwrap() == entry.1.character {
         count+= 1;
     }
 
@@ -70,8 +72,8 @@ fn check_type2_validity(entry: &PasswordEntry) -> bool{
         "Checking rule for {}, looking for character {} at {} or {}, count of finds {}",
         entry.0,
         entry.1.character,
-        entry.1.min,
-        entry.1.max,
+        entry.1.range.start,
+        entry.1.range.end,
         count);
     return count == 1;
 }
@@ -96,8 +98,8 @@ fn part2(lines: &Vec<String>) -> i32 {
     return count;
 }
 
-pub fn run(filename: impl AsRef<Path>) {
-    let lines = io::lines_from_file(filename);
+pub fn run(source: &io::InputSource) {
+    let lines = source.lines();
     let now = Instant::now();
     part1(&lines);
     log::info!("Part 1: {}ms", now.elapsed().as_millis());
@@ -110,26 +112,28 @@ mod tests {
     use super::*;
 
     #[test]
-    fn parse_rule_string_test() {
-        let rule_str = "1-3 a: abcde";
-        let password_rule = parse_rule_string(&rule_str);
-        assert_eq!(true, password_rule.is_ok());
+    fn rule_parser_test() {
+        let rule_str = "1-3 a";
+        let password_rule = parse::parse_all(rule_parser(), rule_str);
+        assert!(password_rule.is_ok());
 
         let unwrapped = password_rule.unwrap();
-        assert_eq!(1, unwrapped.min);
-        assert_eq!(3, unwrapped.max);
+        assert_eq!(Interval::new(1, 3), unwrapped.range);
         assert_eq!('a', unwrapped.character);
+
+        assert_eq!(1, parse::parse_all(rule_parser(), "1_3 a").unwrap_err().position);
+        assert!(parse::parse_all(rule_parser(), "1-3 a: abcde").is_err());
     }
 
     #[test]
     fn parse_file_test() {
-        let lines = vec!["1-3 a: abcde"].iter().map(|x| x.to_string()).collect();
+        let lines = ["1-3 a: abcde", "1-3 a abcde"].iter().map(|x| x.to_string()).collect();
         let password_entries = parse_file(&lines);
+        assert_eq!(1, password_entries.len());
 
         let entry = password_entries.get(0).unwrap();
         assert_eq!("abcde".to_string(), entry.0);
-        assert_eq!(1, entry.1.min);
-        assert_eq!(3, entry.1.max);
+        assert_eq!(Interval::new(1, 3), entry.1.range);
         assert_eq!('a', entry.1.character);
     }
 
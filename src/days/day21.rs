@@ -0,0 +1,164 @@
+use std::time::Instant;
+use std::collections::HashMap;
+use std::collections::HashSet;
+use std::collections::BTreeMap;
+
+use crate::common::io;
+
+struct Food {
+    ingredients: HashSet<String>,
+    allergens: HashSet<String>
+}
+
+// Split "a b c (contains x, y)" into ingredient and allergen sets
+fn parse_food(line: &str) -> Food {
+    let tokens: Vec<&str> = line.trim_end_matches(')').split(" (contains ").collect();
+    let ingredients: HashSet<String> = tokens[0].split_whitespace().map(|x| x.to_string()).collect();
+    let mut allergens: HashSet<String> = HashSet::new();
+
+    if tokens.len() > 1 {
+        allergens = tokens[1].split(',').map(|x| x.trim().to_string()).collect();
+    }
+
+    log::debug!("input was {}, ingredients {:?}, allergens {:?}", line, ingredients, allergens);
+    Food {ingredients, allergens}
+}
+
+fn parse_file(lines: &[String]) -> Vec<Food> {
+    lines.iter().filter(|x| !x.is_empty()).map(|x| parse_food(x)).collect()
+}
+
+// An allergen can only be in ingredients present in every food that lists it,
+// so the candidates for each allergen are the intersection of those ingredient sets
+fn build_candidates(foods: &[Food]) -> HashMap<String, HashSet<String>> {
+    let mut candidates: HashMap<String, HashSet<String>> = HashMap::new();
+
+    for food in foods {
+        for allergen in &food.allergens {
+            let entry = candidates.entry(allergen.to_string()).or_insert_with(|| food.ingredients.clone());
+            entry.retain(|x| food.ingredients.contains(x));
+        }
+    }
+
+    for (allergen, ingredients) in &candidates {
+        log::debug!("Allergen {} can be in {:?}", allergen, ingredients);
+    }
+    candidates
+}
+
+// Repeatedly pin allergens that have a single candidate left and strike that
+// ingredient from every other allergen, until each allergen has its ingredient
+fn resolve_allergens(candidates: &HashMap<String, HashSet<String>>) -> Result<BTreeMap<String, String>, &'static str> {
+    let mut remaining = candidates.clone();
+    let mut resolved: BTreeMap<String, String> = BTreeMap::new();
+
+    while !remaining.is_empty() {
+        let pinned: Vec<(String, String)> = remaining
+            .iter()
+            .filter(|(_, ingredients)| ingredients.len() == 1)
+            .map(|(allergen, ingredients)| (allergen.to_string(), ingredients.iter().next().unwrap().to_string()))
+            .collect();
+
+        if pinned.is_empty() {
+            return Err("Allergens cannot be resolved by elimination");
+        }
+
+        for (allergen, ingredient) in pinned {
+            log::debug!("Allergen {} is in {}", allergen, ingredient);
+            remaining.remove(&allergen);
+            for ingredients in remaining.values_mut() {
+                ingredients.remove(&ingredient);
+            }
+            resolved.insert(allergen, ingredient);
+        }
+
+        if remaining.values().any(|x| x.is_empty()) {
+            return Err("Allergen left without a candidate ingredient");
+        }
+    }
+
+    Ok(resolved)
+}
+
+// Count appearances of every ingredient that is not a candidate for any allergen
+fn part1(lines: &[String]) -> i32 {
+    log::info!("Running Part 1");
+    let foods = parse_file(lines);
+    let candidates = build_candidates(&foods);
+    let unsafe_ingredients: HashSet<&String> = candidates.values().flatten().collect();
+
+    let mut count = 0;
+    for food in &foods {
+        count += food.ingredients.iter().filter(|x| !unsafe_ingredients.contains(x)).count();
+    }
+
+    log::info!("Safe ingredients appear {} times", count);
+    count as i32
+}
+
+// Resolve each allergen to its ingredient and list them ordered by allergen
+fn part2(lines: &[String]) -> String {
+    log::info!("Running Part 2");
+    let foods = parse_file(lines);
+    let candidates = build_candidates(&foods);
+    let resolved = resolve_allergens(&candidates).unwrap();
+    let dangerous: Vec<String> = resolved.values().cloned().collect();
+    let list = dangerous.join(",");
+
+    log::info!("Canonical dangerous ingredient list is {}", list);
+    list
+}
+
+pub fn run(source: &io::InputSource) {
+    let lines = source.lines();
+    let now = Instant::now();
+    part1(&lines);
+    log::info!("Part 1: {}ms", now.elapsed().as_millis());
+    part2(&lines);
+    log::info!("Part 1 + 2: {}ms", now.elapsed().as_millis());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn example() -> Vec<String> {
+        ["mxmxvkd kfcds sqjhc nhms (contains dairy, fish)",
+            "trh fvjkl sbzzf mxmxvkd (contains dairy)",
+            "sqjhc fvjkl (contains soy)",
+            "sqjhc mxmxvkd sbzzf (contains fish)"].iter().map(|x| x.to_string()).collect()
+    }
+
+    #[test]
+    fn parse_food_test() {
+        let food = parse_food("sqjhc fvjkl (contains soy, fish)");
+        assert_eq!(2, food.ingredients.len());
+        assert!(food.ingredients.contains("fvjkl"));
+        assert_eq!(2, food.allergens.len());
+        assert!(food.allergens.contains("fish"));
+    }
+
+    #[test]
+    fn resolve_allergens_test() {
+        let foods = parse_file(&example());
+        let resolved = resolve_allergens(&build_candidates(&foods)).unwrap();
+        assert_eq!("mxmxvkd", resolved["dairy"]);
+        assert_eq!("sqjhc", resolved["fish"]);
+        assert_eq!("fvjkl", resolved["soy"]);
+
+        let mut ambiguous: HashMap<String, HashSet<String>> = HashMap::new();
+        ambiguous.insert("dairy".to_string(), ["a", "b"].iter().map(|x| x.to_string()).collect());
+        ambiguous.insert("fish".to_string(), ["a", "b"].iter().map(|x| x.to_string()).collect());
+        assert!(resolve_allergens(&ambiguous).is_err());
+    }
+
+    #[test]
+    fn part1_test() {
+        assert_eq!(5, part1(&example()));
+    }
+
+    #[test]
+    fn part2_test() {
+        assert_eq!("mxmxvkd,sqjhc,fvjkl", part2(&example()));
+    }
+}
@@ -0,0 +1,258 @@
+use std::time::Instant;
+use std::collections::HashSet;
+use std::collections::VecDeque;
+
+use crate::common::io;
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+struct Deck {
+    cards: VecDeque<u8>
+}
+
+impl Deck {
+    fn draw(&mut self) -> u8 {
+        self.cards.pop_front().unwrap()
+    }
+
+    fn take(&mut self, winner: u8, loser: u8) {
+        self.cards.push_back(winner);
+        self.cards.push_back(loser);
+    }
+
+    // A copy of the next `count` cards, used as the deck for a sub-game
+    fn sub_deck(&self, count: usize) -> Deck {
+        Deck {cards: self.cards.iter().take(count).cloned().collect()}
+    }
+
+    fn len(&self) -> usize {
+        self.cards.len()
+    }
+
+    fn is_empty(&self) -> bool {
+        self.cards.is_empty()
+    }
+
+    // Bottom card is multiplied by 1, the one above it by 2 and so on
+    fn score(&self) -> i64 {
+        self.cards
+            .iter()
+            .rev()
+            .enumerate()
+            .map(|(i, card)| (i as i64 + 1) * *card as i64)
+            .sum()
+    }
+}
+
+// Both decks packed into one buffer, cards are never 0 so it works as the
+// separator. Much cheaper to hash and store than a pair of cloned decks
+fn state_key(player1: &Deck, player2: &Deck) -> Vec<u8> {
+    let mut key: Vec<u8> = Vec::with_capacity(player1.len() + player2.len() + 1);
+    key.extend(player1.cards.iter());
+    key.push(0);
+    key.extend(player2.cards.iter());
+    key
+}
+
+// Decks are blank line separated blocks headed by "Player N:"
+fn parse_decks(lines: &[String]) -> (Deck, Deck) {
+    let mut decks: Vec<Deck> = Vec::new();
+
+    for line in lines {
+        if line.starts_with("Player") {
+            decks.push(Deck {cards: VecDeque::new()});
+        } else if !line.is_empty() {
+            decks.last_mut().unwrap().cards.push_back(line.trim().parse().unwrap());
+        }
+    }
+
+    (decks[0].clone(), decks[1].clone())
+}
+
+// Higher card wins the round, play until one deck is empty
+fn play_combat(player1: &mut Deck, player2: &mut Deck) -> bool {
+    let mut round = 0;
+
+    while !player1.is_empty() && !player2.is_empty() {
+        round += 1;
+        log::debug!("-- Round {} --", round);
+        log::debug!("Player 1's deck: {:?}", player1.cards);
+        log::debug!("Player 2's deck: {:?}", player2.cards);
+
+        let card1 = player1.draw();
+        let card2 = player2.draw();
+        log::debug!("Player 1 plays: {}, Player 2 plays: {}", card1, card2);
+
+        if card1 > card2 {
+            log::debug!("Player 1 wins the round!");
+            player1.take(card1, card2);
+        } else {
+            log::debug!("Player 2 wins the round!");
+            player2.take(card2, card1);
+        }
+    }
+
+    !player1.is_empty()
+}
+
+// Like combat, but a repeated deck state ends the game in favour of player 1 and
+// rounds where both players hold enough cards are decided by a sub-game.
+// With shortcut, sub-games player 1 is bound to win are not played, so they
+// are missing from the debug trace and from the game count.
+// Returns true if player 1 wins
+fn play_recursive_combat(player1: &mut Deck, player2: &mut Deck, game: &mut i32, shortcut: bool) -> bool {
+    *game += 1;
+    let this_game = *game;
+    let mut seen: HashSet<Vec<u8>> = HashSet::new();
+    let mut round = 0;
+    log::debug!("=== Game {} ===", this_game);
+
+    while !player1.is_empty() && !player2.is_empty() {
+        round += 1;
+        log::debug!("-- Round {} (Game {}) --", round, this_game);
+        log::debug!("Player 1's deck: {:?}", player1.cards);
+        log::debug!("Player 2's deck: {:?}", player2.cards);
+
+        if !seen.insert(state_key(player1, player2)) {
+            log::debug!("Repeated state, Player 1 wins game {}", this_game);
+            return true;
+        }
+
+        let card1 = player1.draw();
+        let card2 = player2.draw();
+        log::debug!("Player 1 plays: {}, Player 2 plays: {}", card1, card2);
+
+        let player1_wins;
+        if player1.len() >= card1 as usize && player2.len() >= card2 as usize {
+            log::debug!("Playing a sub-game to determine the winner...");
+            let mut sub1 = player1.sub_deck(card1 as usize);
+            let mut sub2 = player2.sub_deck(card2 as usize);
+
+            // Player 1 holding the highest card can never lose it, and the
+            // repeat rule favours player 1, so the sub-game is theirs
+            let max1 = sub1.cards.iter().max().unwrap();
+            let max2 = sub2.cards.iter().max().unwrap();
+            if shortcut && max1 > max2 {
+                player1_wins = true;
+            } else {
+                player1_wins = play_recursive_combat(&mut sub1, &mut sub2, game, shortcut);
+            }
+            log::debug!("...anyway, back to game {}", this_game);
+        } else {
+            player1_wins = card1 > card2;
+        }
+
+        if player1_wins {
+            log::debug!("Player 1 wins round {} of game {}!", round, this_game);
+            player1.take(card1, card2);
+        } else {
+            log::debug!("Player 2 wins round {} of game {}!", round, this_game);
+            player2.take(card2, card1);
+        }
+    }
+
+    !player1.is_empty()
+}
+
+fn part1(lines: &[String]) -> i64 {
+    log::info!("Running Part 1");
+    let (mut player1, mut player2) = parse_decks(lines);
+    let score = if play_combat(&mut player1, &mut player2) {
+        player1.score()
+    } else {
+        player2.score()
+    };
+    log::info!("Winning player's score is {}", score);
+    score
+}
+
+fn part2(lines: &[String]) -> i64 {
+    log::info!("Running Part 2");
+    let (mut player1, mut player2) = parse_decks(lines);
+    let mut games = 0;
+    // Play every sub-game when the trace is on so it matches the puzzle's one
+    let shortcut = !log::log_enabled!(log::Level::Debug);
+    let score = if play_recursive_combat(&mut player1, &mut player2, &mut games, shortcut) {
+        player1.score()
+    } else {
+        player2.score()
+    };
+    log::info!("Winning player's score is {} after {} games", score, games);
+    score
+}
+
+pub fn run(source: &io::InputSource) {
+    let lines = source.lines();
+    let now = Instant::now();
+    part1(&lines);
+    log::info!("Part 1: {}us", now.elapsed().as_micros());
+    part2(&lines);
+    log::info!("Part 1 + 2: {}ms", now.elapsed().as_millis());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn example() -> Vec<String> {
+        vec![
+            "Player 1:", "9", "2", "6", "3", "1",
+            "",
+            "Player 2:", "5", "8", "4", "7", "10"
+        ].iter().map(|x| x.to_string()).collect()
+    }
+
+    #[test]
+    fn part1_test() {
+        assert_eq!(306, part1(&example()));
+    }
+
+    #[test]
+    fn part2_test() {
+        assert_eq!(291, part2(&example()));
+    }
+
+    #[test]
+    fn recursive_combat_terminates_test() {
+        let lines: Vec<String> = ["Player 1:", "43", "19", "", "Player 2:", "2", "29", "14"]
+            .iter().map(|x| x.to_string()).collect();
+        let (mut player1, mut player2) = parse_decks(&lines);
+        let mut games = 0;
+        assert!(play_recursive_combat(&mut player1, &mut player2, &mut games, true));
+    }
+
+    #[test]
+    fn recursive_combat_game_count_test() {
+        let (mut player1, mut player2) = parse_decks(&example());
+        let mut games = 0;
+        assert!(!play_recursive_combat(&mut player1, &mut player2, &mut games, false));
+        assert_eq!(291, player2.score());
+        assert_eq!(5, games);
+
+        // Player 1 holds the 8 in the only sub-game, which the shortcut skips
+        let decks: Vec<String> = [
+            "Player 1:", "2", "8", "3",
+            "",
+            "Player 2:", "1", "5", "4"
+        ].iter().map(|x| x.to_string()).collect();
+        let mut scores = Vec::new();
+        for (shortcut, expected_games) in [(false, 2), (true, 1)] {
+            let (mut player1, mut player2) = parse_decks(&decks);
+            let mut games = 0;
+            let player1_wins = play_recursive_combat(&mut player1, &mut player2, &mut games, shortcut);
+            assert_eq!(expected_games, games);
+            scores.push((player1_wins, player1.score(), player2.score()));
+        }
+        assert_eq!(scores[0], scores[1]);
+    }
+
+    #[test]
+    fn state_key_test() {
+        let (player1, player2) = parse_decks(&example());
+        assert_eq!(vec![9, 2, 6, 3, 1, 0, 5, 8, 4, 7, 10], state_key(&player1, &player2));
+        // Moving a card between decks has to change the key
+        let moved = Deck {cards: player1.cards.iter().take(4).cloned().collect()};
+        let mut gained = player2.clone();
+        gained.cards.push_front(1);
+        assert_ne!(state_key(&player1, &player2), state_key(&moved, &gained));
+    }
+}
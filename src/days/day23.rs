@@ -0,0 +1,118 @@
+use std::time::Instant;
+
+use crate::common::io;
+
+// Circle of cups stored as a successor array, next[cup] is the cup clockwise of it.
+// Cups are labelled 1..=n so index 0 is unused, moving three cups is then three
+// pointer updates instead of shifting or hashing
+struct Cups {
+    next: Vec<u32>,
+    current: u32
+}
+
+impl Cups {
+    // Labels from the input first, then total_cups - labels.len() more cups counting up
+    fn new(labels: &[u32], total_cups: usize) -> Cups {
+        let mut order: Vec<u32> = labels.to_vec();
+        order.extend((labels.len() as u32 + 1)..(total_cups as u32 + 1));
+
+        let mut next: Vec<u32> = vec![0; total_cups + 1];
+        for i in 0..order.len() {
+            next[order[i] as usize] = order[(i + 1) % order.len()];
+        }
+
+        Cups {next, current: order[0]}
+    }
+
+    fn play_move(&mut self) {
+        let max = (self.next.len() - 1) as u32;
+        let first = self.next[self.current as usize];
+        let second = self.next[first as usize];
+        let third = self.next[second as usize];
+
+        // Lift the three cups out of the circle
+        self.next[self.current as usize] = self.next[third as usize];
+
+        let mut destination = self.current;
+        loop {
+            destination = if destination == 1 { max } else { destination - 1 };
+            if destination != first && destination != second && destination != third {
+                break;
+            }
+        }
+        log::trace!("Current {} picked up {} {} {} destination {}", self.current, first, second, third, destination);
+
+        // Splice them back in after the destination
+        self.next[third as usize] = self.next[destination as usize];
+        self.next[destination as usize] = first;
+        self.current = self.next[self.current as usize];
+    }
+
+    fn play(&mut self, moves: usize) {
+        for _ in 0..moves {
+            self.play_move();
+        }
+    }
+
+    // Labels clockwise after cup 1, excluding 1 itself
+    fn labels_after_one(&self) -> String {
+        let mut labels = String::new();
+        let mut cup = self.next[1];
+        while cup != 1 {
+            labels.push_str(&cup.to_string());
+            cup = self.next[cup as usize];
+        }
+        labels
+    }
+}
+
+fn parse_labels(line: &str) -> Vec<u32> {
+    line.trim().chars().map(|x| x.to_digit(10).unwrap()).collect()
+}
+
+fn part1(labels: &[u32], moves: usize) -> String {
+    log::info!("Running Part 1");
+    let mut cups = Cups::new(labels, labels.len());
+    cups.play(moves);
+    let result = cups.labels_after_one();
+    log::info!("Labels after cup 1 are {}", result);
+    result
+}
+
+fn part2(labels: &[u32]) -> i64 {
+    log::info!("Running Part 2");
+    let mut cups = Cups::new(labels, 1000000);
+    cups.play(10000000);
+    let first = cups.next[1];
+    let second = cups.next[first as usize];
+    let product = first as i64 * second as i64;
+    log::info!("Cups after cup 1 are {} and {}, product {}", first, second, product);
+    product
+}
+
+pub fn run(source: &io::InputSource) {
+    let labels = parse_labels(source.lines().first().unwrap());
+    let now = Instant::now();
+    part1(&labels, 100);
+    log::info!("Part 1: {}us", now.elapsed().as_micros());
+    part2(&labels);
+    log::info!("Part 1 + 2: {}ms", now.elapsed().as_millis());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn part1_test() {
+        let labels = parse_labels("389125467");
+        assert_eq!("92658374", part1(&labels, 10));
+        assert_eq!("67384529", part1(&labels, 100));
+    }
+
+    #[test]
+    fn part2_test() {
+        let labels = parse_labels("389125467");
+        assert_eq!(149245887792, part2(&labels));
+    }
+}
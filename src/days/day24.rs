@@ -0,0 +1,107 @@
+use std::time::Instant;
+use std::collections::HashSet;
+
+use crate::common::io;
+use crate::common::hex;
+use crate::common::hex::Hex;
+use crate::common::automaton::SparseAutomaton;
+
+// Walk every path from the reference tile and flip the tile it ends on
+fn flip_tiles(lines: &[String]) -> HashSet<Hex> {
+    let mut black: HashSet<Hex> = HashSet::new();
+
+    for line in lines {
+        if line.is_empty() {
+            continue;
+        }
+        let mut tile = Hex::default();
+        for dir in hex::parse_path(line).unwrap() {
+            tile = tile.step(dir);
+        }
+        log::debug!("Path {} ends at {:?}", line, tile);
+
+        if !black.remove(&tile) {
+            black.insert(tile);
+        }
+    }
+
+    black
+}
+
+// Black tiles with zero or more than 2 black neighbors flip to white, white
+// tiles with exactly 2 black neighbors flip to black
+fn tile_rule(black: bool, black_neighbors: usize) -> bool {
+    black_neighbors == 2 || (black && black_neighbors == 1)
+}
+
+fn part1(lines: &[String]) -> i32 {
+    log::info!("Running Part 1");
+    let count = flip_tiles(lines).len() as i32;
+    log::info!("Found {} tiles with the black side up", count);
+    count
+}
+
+fn part2(lines: &[String], days: i32) -> i32 {
+    log::info!("Running Part 2");
+    let mut floor = SparseAutomaton::new(flip_tiles(lines), |tile: &Hex| tile.neighbors(), tile_rule);
+
+    for _ in 0..days {
+        floor.step();
+        log::debug!("Day {}: {}", floor.generation(), floor.live().len());
+    }
+
+    let count = floor.live().len() as i32;
+    log::info!("Found {} tiles with the black side up after {} days", count, days);
+    count
+}
+
+pub fn run(source: &io::InputSource) {
+    let lines = source.lines();
+    let now = Instant::now();
+    part1(&lines);
+    log::info!("Part 1: {}us", now.elapsed().as_micros());
+    part2(&lines, 100);
+    log::info!("Part 1 + 2: {}ms", now.elapsed().as_millis());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn example() -> Vec<String> {
+        vec![
+            "sesenwnenenewseeswwswswwnenewsewsw",
+            "neeenesenwnwwswnenewnwwsewnenwseswesw",
+            "seswneswswsenwwnwse",
+            "nwnwneseeswswnenewneswwnewseswneseene",
+            "swweswneswnenwsewnwneneseenw",
+            "eesenwseswswnenwswnwnwsewwnwsene",
+            "sewnenenenesenwsewnenwwwse",
+            "wenwwweseeeweswwwnwwe",
+            "wsweesenenewnwwnwsenewsenwwsesesenwne",
+            "neeswseenwwswnwswswnw",
+            "nenwswwsewswnenenewsenwsenwnesesenew",
+            "enewnwewneswsewnwswenweswnenwsenwsw",
+            "sweneswneswneneenwnewenewwneswswnese",
+            "swwesenesewenwneswnwwneseswwne",
+            "enesenwswwswneneswsenwnewswseenwsese",
+            "wnwnesenesenenwwnenwsewesewsesesew",
+            "nenewswnwewswnenesenwnesewesw",
+            "eneswnwswnwsenenwnwnwwseeswneewsenese",
+            "neswnwewnwnwseenwseesewsenwsweewe",
+            "wseweeenwnesenwwwswnew"
+        ].iter().map(|x| x.to_string()).collect()
+    }
+
+    #[test]
+    fn part1_test() {
+        assert_eq!(10, part1(&example()));
+    }
+
+    #[test]
+    fn part2_test() {
+        assert_eq!(15, part2(&example(), 1));
+        assert_eq!(37, part2(&example(), 10));
+        assert_eq!(2208, part2(&example(), 100));
+    }
+}
@@ -0,0 +1,49 @@
+use std::time::Instant;
+
+use crate::common::io;
+use crate::common::math;
+
+const SUBJECT: u64 = 7;
+const MODULUS: u64 = 20201227;
+
+// The public key is 7^loop_size mod 20201227, so the loop size is its discrete log
+fn find_loop_size(public_key: u64) -> u64 {
+    let loop_size = math::discrete_log(SUBJECT, public_key, MODULUS).expect("Public key is not a power of the subject number");
+    log::debug!("Public key {} has loop size {}", public_key, loop_size);
+    loop_size
+}
+
+// Either device transforms the other's public key with its own loop size
+fn part1(card_key: u64, door_key: u64) -> u64 {
+    log::info!("Running Part 1");
+    let card_loop_size = find_loop_size(card_key);
+    let encryption_key = math::mod_pow(door_key, card_loop_size, MODULUS);
+    log::info!("Found the encryption key to be {}", encryption_key);
+    encryption_key
+}
+
+pub fn run(source: &io::InputSource) {
+    let lines = source.lines();
+    let card_key: u64 = lines[0].trim().parse().unwrap();
+    let door_key: u64 = lines[1].trim().parse().unwrap();
+    let now = Instant::now();
+    part1(card_key, door_key);
+    log::info!("Part 1: {}us", now.elapsed().as_micros());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn find_loop_size_test() {
+        assert_eq!(8, find_loop_size(5764801));
+        assert_eq!(11, find_loop_size(17807724));
+    }
+
+    #[test]
+    fn part1_test() {
+        assert_eq!(14897079, part1(5764801, 17807724));
+        assert_eq!(14897079, part1(17807724, 5764801));
+    }
+}
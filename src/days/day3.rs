@@ -1,39 +1,34 @@
-use std::path::Path as Path;
 use std::time::Instant;
 
 use crate::common::io;
+use crate::common::grid::Grid;
 
 // Simple matrix traversal downward, when lateral exceeds, wrap around with counter for #
-fn traverse_matrix(lines: &Vec<String>, x_move: i8, y_move: i8) -> i32 {
-    let mut x: i32 = 0;
-    let mut y: i32 = 0;
-
-    let x_max = lines.iter().next().unwrap().len() as i32;
-    let y_max = lines.len() as i32;
-
+fn traverse_matrix(matrix: &Grid<char>, x_move: i8, y_move: i8) -> i32 {
+    let mut x: i64 = 0;
+    let mut y: i64 = 0;
     let mut count = 0;
 
-    while y < y_max {
-        let entry = lines[y as usize].chars().nth(x as usize).unwrap();
-        if entry == '#' {
+    while y < matrix.height() as i64 {
+        if *matrix.get_wrapping(x, y) == '#' {
             count += 1;
         }
 
-        x = (x + x_move as i32) % x_max;
-        y += y_move as i32;
+        x += x_move as i64;
+        y += y_move as i64;
     }
 
     return count;
 }
 
-fn part1(matrix: &Vec<String>) -> i32 {
+fn part1(matrix: &Grid<char>) -> i32 {
     log::info!("Running Part 1");
     let count = traverse_matrix(matrix, 3, 1);
     log::info!("Found {} trees on the way down", count);
     return count;
 }
 
-fn part2(matrix: &Vec<String>) -> i32 {
+fn part2(matrix: &Grid<char>) -> i32 {
     log::info!("Running Part 2");
     let mut result = 1;
     result *= traverse_matrix(matrix, 1, 1);
@@ -45,12 +40,13 @@ fn part2(matrix: &Vec<String>) -> i32 {
     return result;
 }
 
-pub fn run(filename: impl AsRef<Path>) {
-    let lines = io::lines_from_file(filename);
+pub fn run(source: &io::InputSource) {
+    let input = io::Input::from_source(source);
+    let matrix = Grid::parse(&input.line_vec()).unwrap();
     let now = Instant::now();
-    part1(&lines);
+    part1(&matrix);
     log::info!("Part 1: {}us", now.elapsed().as_micros());
-    part2(&lines);
+    part2(&matrix);
     log::info!("Part 1 + 2: {}ms", now.elapsed().as_millis());
 }
 
@@ -71,9 +67,8 @@ mod tests {
                  ".#........#",
                  "#.##...#...",
                  "#...##....#",
-                 ".#..#...#.#"]
-            .iter().map(|x| x.to_string()).collect();
-        assert_eq!(7, part1(&matrix));
+                 ".#..#...#.#"];
+        assert_eq!(7, part1(&Grid::parse(&matrix).unwrap()));
     }
 
     #[test]
@@ -89,8 +84,7 @@ mod tests {
                  ".#........#",
                  "#.##...#...",
                  "#...##....#",
-                 ".#..#...#.#"]
-            .iter().map(|x| x.to_string()).collect();
-        assert_eq!(336, part2(&matrix));
+                 ".#..#...#.#"];
+        assert_eq!(336, part2(&Grid::parse(&matrix).unwrap()));
     }
 }
\ No newline at end of file
@@ -1,126 +1,85 @@
-use std::path::Path as Path;
 use std::time::Instant;
-use regex::RegexSet;
+use std::collections::HashMap;
 use regex::Regex;
 
 use crate::common::io;
-use crate::common::convertor::chunk_parts as chunk_parts;
+use crate::common::convertor;
+use crate::common::interval::Interval;
 
-// Simple prefix regex
-fn part1(lines: &Vec<String>) -> i32 {
-    log::info!("Running Part 1");
-    let regex_set = RegexSet::new(&[
-        r"byr:",
-        r"iyr:",
-        r"eyr:",
-        r"hgt:",
-        r"hcl:",
-        r"ecl:",
-        r"pid:",
-    ]).unwrap();
-    
-    let mut count = 0;
-    for line in lines {
-        let matches: Vec<_> = regex_set.matches(line).into_iter().collect();
-        if matches.len() == regex_set.len() {
-            count += 1;
-        }
-    }
+type Passport<'a> = HashMap<&'a str, &'a str>;
 
-    log::info!("Found {} valid entries", count);
-    return count;
+const REQUIRED_FIELDS: [&str; 7] = ["byr", "iyr", "eyr", "hgt", "hcl", "ecl", "pid"];
+
+fn parse_passports<'a, S: AsRef<str>>(lines: &'a [S]) -> Vec<Passport<'a>> {
+    return convertor::split_records(lines)
+        .iter()
+        .map(|x| convertor::parse_key_values(x).unwrap())
+        .collect();
 }
 
-fn validate_date_range_regex(regex: &Regex, line: &String, min: i16, max: i16) -> bool {
-    let cap_grp = regex.captures(line);
-    if cap_grp.is_some() {
-        let cap = cap_grp.unwrap();
-        if cap.len() > 0 {
-            let year: i16 = cap.get(1).unwrap().as_str().parse().unwrap();
-            return year >= min && year <= max;
-        }
-    }
-    return false;
+fn has_required_fields(passport: &Passport) -> bool {
+    return REQUIRED_FIELDS.iter().all(|x| passport.contains_key(x));
 }
 
-fn validate_height_range_regex(regex: &Regex, line: &String) -> bool {
-    let cap_grp = regex.captures(line);
-    if cap_grp.is_some() {
-        let cap = cap_grp.unwrap();
-        if cap.len() > 0 {
-            let height = cap.get(1).unwrap().as_str();
-            if height.contains("cm") {
-                let height_cm: i16 = height.replace("cm", "").parse().unwrap();
-                return height_cm >= 150 && height_cm <= 193;
-            }
-
-            if height.contains("in") {
-                let height_cm: i16 = height.replace("in", "").parse().unwrap();
-                return height_cm >= 59 && height_cm <= 76;
-            }
-        }
-    }
-    return false;
+// All required fields present, cid is optional
+fn part1(passports: &Vec<Passport>) -> i32 {
+    log::info!("Running Part 1");
+    let count = passports.iter().filter(|x| has_required_fields(x)).count() as i32;
+
+    log::info!("Found {} valid entries", count);
+    return count;
 }
 
-fn verify_hex_regex(regex: &Regex, line: &String) -> bool {
-    let cap_grp = regex.captures(line);
-    if cap_grp.is_some() {
-        let cap = cap_grp.unwrap();
-        if cap.len() > 0 {
-            let length = cap.get(1).unwrap().as_str().len();
-            return length == 6;
-        }
+fn validate_date_range(value: &str, years: Interval<i16>) -> bool {
+    if value.len() != 4 {
+        return false;
     }
-    return false;
+    return match value.parse::<i16>() {
+        Ok(year) => years.contains(year),
+        Err(_) => false
+    };
 }
 
-fn verify_eye_color_regex(regex: &Regex, line: &String) -> bool {
-    let cap_grp = regex.captures(line);
-    let colors = vec!["amb", "blu", "brn", "gry", "grn", "hzl", "oth"];
-    if cap_grp.is_some() {
-        let cap = cap_grp.unwrap();
-        if cap.len() > 0 {
-            let color = cap.get(1).unwrap().as_str();
-            return colors.iter().any(|&c| c == color);
-        }
+fn validate_height_range_regex(regex: &Regex, value: &str) -> bool {
+    let cap_grp = regex.captures(value);
+    if let Some(cap) = cap_grp {
+        // The digits can still be too many for an i16, no such height is valid
+        let height: i16 = match cap.get(1).unwrap().as_str().parse() {
+            Ok(height) => height,
+            Err(_) => return false
+        };
+        return match cap.get(2).unwrap().as_str() {
+            "cm" => Interval::new(150, 193).contains(height),
+            "in" => Interval::new(59, 76).contains(height),
+            _ => false
+        };
     }
     return false;
 }
 
-fn verify_pid_regex(regex: &Regex, line: &String) -> bool {
-    let cap_grp = regex.captures(line);
-    if cap_grp.is_some() {
-        let cap = cap_grp.unwrap();
-        if cap.len() > 0 {
-            let pid = cap.get(1).unwrap().as_str();
-            return pid.len() == 9;
-        }
-    }
-    return false;
+fn verify_eye_color(value: &str) -> bool {
+    let colors = ["amb", "blu", "brn", "gry", "grn", "hzl", "oth"];
+    return colors.contains(&value);
 }
 
-// Check for regex match and extract value to check validity rule
-fn part2(lines: &Vec<String>) -> i32 {
+// Look up each field and check its value against the field's rule
+fn part2(passports: &Vec<Passport>) -> i32 {
     log::info!("Running Part 2");
-    let byr = Regex::new(r"byr:(\d{4})").unwrap();
-    let iyr = Regex::new(r"iyr:(\d{4})").unwrap();
-    let eyr = Regex::new(r"eyr:(\d{4})").unwrap();
-    let hgt = Regex::new(r"hgt:([0-9]+[a-z]*)").unwrap();
-    let hcl = Regex::new(r"hcl:#([0-9a-f]*)").unwrap();
-    let ecl = Regex::new(r"ecl:(\w{3})").unwrap();
-    let pid = Regex::new(r"pid:(\d{9})").unwrap();
+    let hgt = Regex::new(r"^([0-9]+)(cm|in)$").unwrap();
+    let hcl = Regex::new(r"^#[0-9a-f]{6}$").unwrap();
+    let pid = Regex::new(r"^[0-9]{9}$").unwrap();
     
     let mut count = 0;
-    for line in lines {
-        log::debug!("Processing line {}", line);
-        if validate_date_range_regex(&byr, line, 1920, 2002) &&
-           validate_date_range_regex(&iyr, line, 2010, 2020) &&
-           validate_date_range_regex(&eyr, line, 2020, 2030) &&
-           validate_height_range_regex(&hgt, line) &&
-           verify_hex_regex(&hcl, line) &&
-           verify_eye_color_regex(&ecl, line) &&
-           verify_pid_regex(&pid, line) {
+    for passport in passports {
+        log::debug!("Processing passport {:?}", passport);
+        if has_required_fields(passport) &&
+           validate_date_range(passport["byr"], Interval::new(1920, 2002)) &&
+           validate_date_range(passport["iyr"], Interval::new(2010, 2020)) &&
+           validate_date_range(passport["eyr"], Interval::new(2020, 2030)) &&
+           validate_height_range_regex(&hgt, passport["hgt"]) &&
+           hcl.is_match(passport["hcl"]) &&
+           verify_eye_color(passport["ecl"]) &&
+           pid.is_match(passport["pid"]) {
             count += 1;
            }
 
@@ -129,13 +88,14 @@ fn part2(lines: &Vec<String>) -> i32 {
     return count;
 }
 
-pub fn run(filename: impl AsRef<Path>) {
-    let lines = io::lines_from_file(filename);
-    let chunks = chunk_parts(&lines);
+pub fn run(source: &io::InputSource) {
+    let input = io::Input::from_source(source);
+    let lines = input.line_vec();
+    let passports = parse_passports(&lines);
     let now = Instant::now();
-    part1(&chunks);
+    part1(&passports);
     log::info!("Part 1: {}ms", now.elapsed().as_millis());
-    part2(&chunks);
+    part2(&passports);
     log::info!("Part 1 + 2: {}ms", now.elapsed().as_millis());
 }
 
@@ -145,7 +105,7 @@ mod tests {
 
     #[test]
     fn part1_test() {
-        let lines = vec![        
+        let lines: Vec<String> = vec![
             "ecl:gry pid:860033327 eyr:2020 hcl:#fffffd",
             "byr:1937 iyr:2017 cid:147 hgt:183cm",
             "",
@@ -161,37 +121,37 @@ mod tests {
             "iyr:2011 ecl:brn hgt:59in"]
             .iter().map(|x| x.to_string()).collect();
         
-        let chunked_lines = chunk_parts(&lines);
+        let passports = parse_passports(&lines);
 
-        assert_eq!(2, part1(&chunked_lines));
+        assert_eq!(2, part1(&passports));
     }
 
     #[test]
     fn regex_test() {
-        let byr = Regex::new(r"byr:(\d{4})").unwrap();
-        let hgt = Regex::new(r"hgt:([0-9]+[a-z]*)").unwrap();
-        let hcl = Regex::new(r"hcl:#([0-9a-f]*)").unwrap();
-        let ecl = Regex::new(r"ecl:(\w{3})").unwrap();
-        let pid = Regex::new(r"pid:([0-9]+)").unwrap();
+        let hgt = Regex::new(r"^([0-9]+)(cm|in)$").unwrap();
+        let hcl = Regex::new(r"^#[0-9a-f]{6}$").unwrap();
+        let pid = Regex::new(r"^[0-9]{9}$").unwrap();
         
-        assert_eq!(true, validate_date_range_regex(&byr, &"byr:2002".to_string(), 1920, 2002));
-        assert_eq!(false, validate_date_range_regex(&byr, &"byr:2003".to_string(), 1920, 2002));
-        assert_eq!(true, validate_height_range_regex(&hgt, &"hgt:60in".to_string()));
-        assert_eq!(true, validate_height_range_regex(&hgt, &"hgt:190cm".to_string()));
-        assert_eq!(false, validate_height_range_regex(&hgt, &"hgt:190in".to_string()));
-        assert_eq!(false, validate_height_range_regex(&hgt, &"hgt:190".to_string()));
-        assert_eq!(true, verify_hex_regex(&hcl, &"hcl:#123abc".to_string()));
-        assert_eq!(false, verify_hex_regex(&hcl, &"hcl:#123abz".to_string()));
-        assert_eq!(false, verify_hex_regex(&hcl, &"hcl:123abc".to_string()));
-        assert_eq!(true, verify_eye_color_regex(&ecl, &"ecl:brn".to_string()));
-        assert_eq!(false, verify_eye_color_regex(&ecl, &"ecl:wat".to_string()));
-        assert_eq!(true, verify_pid_regex(&pid, &"pid:000000001".to_string()));
-        assert_eq!(false, verify_pid_regex(&pid, &"pid:0123456789".to_string()));
+        assert_eq!(true, validate_date_range("2002", Interval::new(1920, 2002)));
+        assert_eq!(false, validate_date_range("2003", Interval::new(1920, 2002)));
+        assert_eq!(true, validate_height_range_regex(&hgt, "60in"));
+        assert_eq!(true, validate_height_range_regex(&hgt, "190cm"));
+        assert_eq!(false, validate_height_range_regex(&hgt, "190in"));
+        assert_eq!(false, validate_height_range_regex(&hgt, "190"));
+        assert!(!validate_height_range_regex(&hgt, "99999cm"));
+        assert!(!validate_height_range_regex(&hgt, "999999999999in"));
+        assert_eq!(true, hcl.is_match("#123abc"));
+        assert_eq!(false, hcl.is_match("#123abz"));
+        assert_eq!(false, hcl.is_match("123abc"));
+        assert_eq!(true, verify_eye_color("brn"));
+        assert_eq!(false, verify_eye_color("wat"));
+        assert_eq!(true, pid.is_match("000000001"));
+        assert_eq!(false, pid.is_match("0123456789"));
     }
 
     #[test]
     fn part2_test_invalid() {
-        let lines = vec![
+        let lines: Vec<String> = vec![
             "eyr:1972 cid:100",
             "hcl:#18171d ecl:amb hgt:170 pid:186cm iyr:2018 byr:1926",
             "",
@@ -208,13 +168,13 @@ mod tests {
         ]
         .iter().map(|x| x.to_string()).collect();
 
-        let chunked_lines = chunk_parts(&lines);
-        assert_eq!(0, part2(&chunked_lines));
+        let passports = parse_passports(&lines);
+        assert_eq!(0, part2(&passports));
     }
 
     #[test]
     fn part2_test_valid() {
-        let lines = vec![
+        let lines: Vec<String> = vec![
             "pid:087499704 hgt:74in ecl:grn iyr:2012 eyr:2030 byr:1980",
             "hcl:#623a2f",
             "",
@@ -230,7 +190,7 @@ mod tests {
         ]
         .iter().map(|x| x.to_string()).collect();
 
-        let chunked_lines = chunk_parts(&lines);
-        assert_eq!(4, part2(&chunked_lines));
+        let passports = parse_passports(&lines);
+        assert_eq!(4, part2(&passports));
     }
 }
\ No newline at end of file
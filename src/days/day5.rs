@@ -1,7 +1,7 @@
-use std::path::Path as Path;
 use std::time::Instant;
 
 use crate::common::io;
+use crate::common::search;
 
 fn generate_seat_id(guid: &String) -> i32 {
     let row_string = guid.chars().take(7);
@@ -65,16 +65,15 @@ fn part2(lines: &Vec<String>) {
 
     seats.sort();
 
-    for i in 0..seats.len() {
-        if seats[i] + 1 != seats[i+1] {
-            log::info!("The missing seat number is {}", seats[i] + 1);
-            return;
-        }
+    // Ids are consecutive up to the gap, so the first index that is off is right after it
+    match search::first_true(0, seats.len() as i64 - 1, |i| seats[i as usize] != seats[0] + i as i32) {
+        Some(index) => log::info!("The missing seat number is {}", seats[index as usize] - 1),
+        None => log::info!("No seat is missing")
     }
 }
 
-pub fn run(filename: impl AsRef<Path>) {
-    let lines = io::lines_from_file(filename);
+pub fn run(source: &io::InputSource) {
+    let lines = source.lines();
     let now = Instant::now();
     part1(&lines);
     log::info!("Part 1: {}ms", now.elapsed().as_millis());
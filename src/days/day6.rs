@@ -1,23 +1,26 @@
-use std::path::Path as Path;
 use std::time::Instant;
-use std::collections::HashSet;
 
 use crate::common::io;
 use crate::common::convertor;
+use crate::common::bits::FixedBitSet;
 
-// Chunk replies into group, per group get individual replies, union and sum union sizes
-fn part1(lines: &Vec<String>) -> i32 {
+// Questions answered yes by one person, bit n is the nth letter. Questions
+// are a to z, anything else like a stray '\r' is skipped
+fn answers(individual_answer: &str) -> FixedBitSet {
+    let mut questions = FixedBitSet::new();
+    for character in individual_answer.chars().filter(|x| x.is_ascii_lowercase()) {
+        questions.insert(character as u32 - 'a' as u32);
+    }
+    questions
+}
+
+// Split replies into groups, per group get individual replies, union and sum union sizes
+fn part1(groups: &Vec<Vec<&str>>) -> i32 {
     log::info!("Running Part 1");
     let mut count = 0;
 
-    for line in lines {
-        let mut union_questions: HashSet<char> = HashSet::new();
-        let individual_answers: Vec<&str> = line.split(' ').collect();
-        
-        for individual_answer in individual_answers {
-            let individual_answer_set: HashSet<char> = individual_answer.chars().collect();
-            union_questions.extend(&individual_answer_set);
-        }
+    for group in groups {
+        let union_questions = group.iter().fold(FixedBitSet::new(), |all, x| all.union(&answers(x)));
         count += union_questions.len();
     }
 
@@ -25,40 +28,27 @@ fn part1(lines: &Vec<String>) -> i32 {
     return count as i32;
 }
 
-// Chunk replies into group, per group find number of common respones
-fn part2(lines: &Vec<String>) -> i32 {
+// Split replies into groups, per group find number of common respones
+fn part2(groups: &Vec<Vec<&str>>) -> i32 {
     log::info!("Running Part 2");
     let mut count = 0;
 
-    for line in lines {
-        let individual_answers: Vec<&str> = line.split(' ').collect();
-        let mut group_question_array: [i32; 26] = [0; 26];
-        let token_count = individual_answers.len();
-
-        for individual_answer in individual_answers {
-            for character in individual_answer.chars() {
-                group_question_array[(character as u32 - 'a' as u32) as usize] += 1;
-            }
-        }
-
-        for i in 0..26 {
-            if group_question_array[i] == token_count as i32 {
-                count += 1;
-            }
-        }
+    for group in groups {
+        let common_questions = group.iter().fold(FixedBitSet::full(26), |common, x| common.intersection(&answers(x)));
+        count += common_questions.len();
     }
 
     log::info!("The sum of these counts is {}", count);
     return count as i32;
 }
 
-pub fn run(filename: impl AsRef<Path>) {
-    let lines = io::lines_from_file(filename);
-    let chunks = convertor::chunk_parts(&lines);
+pub fn run(source: &io::InputSource) {
+    let lines = source.lines();
+    let groups = convertor::split_records(&lines);
     let now = Instant::now();
-    part1(&chunks);
+    part1(&groups);
     log::info!("Part 1: {}ms", now.elapsed().as_millis());
-    part2(&chunks);
+    part2(&groups);
     log::info!("Part 1 + 2: {}ms", now.elapsed().as_millis());
 }
 
@@ -66,9 +56,17 @@ pub fn run(filename: impl AsRef<Path>) {
 mod tests {
     use super::*;
 
+    #[test]
+    fn answers_test() {
+        assert_eq!(3, answers("abz").len());
+        assert!(answers("abz").contains(25));
+        assert_eq!(answers("ab"), answers("aB\r b!"));
+        assert!(answers("").is_empty());
+    }
+
     #[test]
     fn part1_test() {
-        let lines = vec![
+        let lines: Vec<String> = vec![
             "abc",
             "",
             "a",
@@ -85,13 +83,13 @@ mod tests {
             "",
             "b"
         ].iter().map(|x| x.to_string()).collect();
-        let chunks = convertor::chunk_parts(&lines);
-        assert_eq!(11, part1(&chunks));
+        let groups = convertor::split_records(&lines);
+        assert_eq!(11, part1(&groups));
     }
 
     #[test]
     fn part2_test() {
-        let lines = vec![
+        let lines: Vec<String> = vec![
             "abc",
             "",
             "a",
@@ -108,7 +106,7 @@ mod tests {
             "",
             "b"
         ].iter().map(|x| x.to_string()).collect();
-        let chunks = convertor::chunk_parts(&lines);
-        assert_eq!(6, part2(&chunks));
+        let groups = convertor::split_records(&lines);
+        assert_eq!(6, part2(&groups));
     }
 }
\ No newline at end of file
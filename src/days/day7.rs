@@ -1,117 +1,107 @@
-use std::path::Path as Path;
 use std::time::Instant;
-use std::collections::HashMap;
-use std::collections::VecDeque;
 
 use crate::common::io;
+use crate::common::graph::{Graph, NodeId};
+use crate::common::memo::Memo;
+use crate::common::parse;
+use crate::common::parse::{Cursor, Step, literal, map, pair, terminated};
+
+type BagGraph = Graph<String, i32>;
+// Outer bag color and the count and color of each bag it directly contains
+type Rule = (String, Vec<(i32, String)>);
+
+// Two word color like "light red"
+fn color<'a>() -> impl Fn(Cursor<'a>) -> Step<'a, String> {
+    map(pair(terminated(parse::word(), literal(" ")), parse::word()), |(shade, hue)| format!("{} {}", shade, hue))
+}
+
+// "light red bags contain 1 bright white bag, 2 muted yellow bags." into the
+// outer color and the count of each color it directly contains
+fn rule_parser<'a>() -> impl Fn(Cursor<'a>) -> Step<'a, Rule> {
+    let bags = terminated(literal(" bag"), parse::optional(literal("s")));
+    let content = pair(terminated(parse::integer::<i32>(), literal(" ")), terminated(color(), bags));
+    let contents = parse::alt(
+        parse::separated(content, literal(", ")),
+        map(literal("no other bags"), |_| Vec::new()));
+    pair(terminated(color(), literal(" bags contain ")), terminated(contents, literal(".")))
+}
 
-fn build_tree_from_rules(lines: &Vec<String>) -> HashMap<String, HashMap<String, i32>> {
-    let mut root: HashMap<String, HashMap<String, i32>> = HashMap::new();
-
-    for line in lines {
-        let mut clone = line.to_string();
-        clone = clone.replace("bag.", "");
-        clone = clone.replace("bags.", "");
-        clone = clone.replace(" bags contain ", "|");
-        clone = clone.replace("bag,", ":");
-        clone = clone.replace("bags,", ":");
-        clone = clone.replace("no other", "");
-
-        let tokens: Vec<&str> = clone.split('|').collect();
-        let root_color = tokens[0].trim().to_string();
-        let contain_rules_string = tokens[1].trim();
-        let contain_rules: Vec<&str> = contain_rules_string.split(':').collect();
-
-        log::debug!("{}", root_color);
-        log::debug!("{}", clone);
-        log::debug!("{}", contain_rules_string);
-        
-        let mut contain: HashMap<String, i32> = HashMap::new();
-        if contain_rules_string.is_empty() {
-            log::debug!("skipping");
-        } else {
-            for rule in contain_rules {
-                let mut rule_tokens: Vec<&str> = rule.trim().split(' ').collect();
-                let count: i32 = rule_tokens.get(0).unwrap().trim().parse().unwrap();
-                let color_words: Vec<&str> = rule_tokens.drain(1..rule_tokens.len()).collect();
-                contain.insert(color_words.join(" "), count);
-            } 
+// Each rule becomes edges from the outer bag to every bag it directly
+// contains, weighted by how many of them it holds
+fn build_graph_from_rules(lines: &Vec<String>) -> BagGraph {
+    let mut graph: BagGraph = Graph::new();
+
+    for (root_color, contents) in parse::parse_lines(rule_parser(), lines).unwrap() {
+        log::debug!("{} contains {:?}", root_color, contents);
+        graph.add_node(root_color.clone());
+        for (count, color) in contents {
+            graph.add_edge(root_color.clone(), color, count);
         }
-        root.insert(root_color, contain);
     }
 
-    return root;
+    return graph;
 }
 
-fn look_for_shiny_gold(tree: &HashMap<String, HashMap<String, i32>>, subtree: &HashMap<String, i32>) -> i32 {
-    let mut total = 1;
-    for key in subtree.keys() {
-        log::debug!("going deeper into color: {}", key);
-        let cost = look_for_shiny_gold(&tree, &tree[key]);
-        total += cost * subtree[key];
-    }
-    return total;
+// Number of bags inside bag, each color is only counted out once
+fn look_for_shiny_gold(graph: &BagGraph, bag: NodeId, memo: &mut Memo<NodeId, i32>) -> i32 {
+    return memo.get(bag, |memo| {
+        let mut total = 0;
+        for (inner, count) in graph.edges(bag) {
+            log::debug!("going deeper into color: {}", graph.node(*inner));
+            total += count * (1 + look_for_shiny_gold(graph, *inner, memo));
+        }
+        return total;
+    });
 }
 
-// For each bag in the input set, traverse downward in a breadth first manner
-// till you find a leaf of gold back, if you found a gold bag count this bag
-fn part1(tree: &HashMap<String, HashMap<String, i32>>) -> i32 {
-    log::info!("Running Part 1");
-    let mut count = 0;
-    for color in tree.keys() {
-        log::debug!("Exploring color: {}", color);
-        
-        let mut queue: VecDeque<&HashMap<String, i32>> = VecDeque::new();
-        queue.push_back(&tree[color]);
-        let mut found = false;
-
-        while !queue.is_empty() {
-            let contains_rules = queue.pop_back().unwrap();
-
-            if contains_rules.contains_key("shiny gold") {
-                log::debug!("The eagle has landed");
-                found = true;
-                continue;
-            }
-
-            for rule in contains_rules.keys() {
-                log::debug!("going deeper into color: {}", rule);
-                queue.push_back(&tree[rule]);
-            }
-        }
+// Whether bag holds target somewhere inside, each color is only explored once
+fn can_contain(graph: &BagGraph, bag: NodeId, target: NodeId, memo: &mut Memo<NodeId, bool>) -> bool {
+    return memo.get(bag, |memo| {
+        return graph.edges(bag).iter().any(|(inner, _)| *inner == target || can_contain(graph, *inner, target, memo));
+    });
+}
 
-        if found {
-            count += 1;
-        }
-    }
+// Ask every color whether it can hold shiny gold, sharing the answers
+// between colors so the whole graph is only walked once
+fn part1(graph: &BagGraph) -> i32 {
+    log::info!("Running Part 1");
+    let gold = graph.id(&"shiny gold".to_string()).unwrap();
 
+    let mut memo = Memo::new();
+    let count = (0..graph.len()).filter(|bag| can_contain(graph, *bag, gold, &mut memo)).count() as i32;
     log::info!("Found {} different bags that can contain shiny gold", count);
     return count;
 }
 
 // Proceed in a depth first manner to find the cost of each bag,
 // the cost is the count of the number of bags that it contains within it recursively
-fn part2(tree: &HashMap<String, HashMap<String, i32>>) -> i32 {
+fn part2(graph: &BagGraph) -> i32 {
     log::info!("Running Part 2");
     let color = "shiny gold";
 
     log::debug!("Exploring color: {}", color);
-    let subtree = &tree[color];
+    let gold = graph.id(&color.to_string()).unwrap();
 
-    // Need to remove the bag itself since we are starting count total at 1
-    // This means that we need to exclude the bag itself hence -1
-    let cost = look_for_shiny_gold(&tree, &subtree) - 1;
+    let cost = look_for_shiny_gold(graph, gold, &mut Memo::new());
     log::info!("Found that the cost of {} is {}", color, cost);
     return cost;
 }
 
-pub fn run(filename: impl AsRef<Path>) {
-    let lines = io::lines_from_file(filename);
-    let tree = build_tree_from_rules(&lines);
+pub fn run(source: &io::InputSource) {
+    let lines = source.lines();
+    let graph = build_graph_from_rules(&lines);
+
+    // Both parts recurse through the rules, a bag ending up inside itself would never finish
+    if let Some(cycle) = graph.find_cycle() {
+        let colors: Vec<&String> = cycle.iter().map(|x| graph.node(*x)).collect();
+        log::error!("Rules are circular: {:?}", colors);
+        return;
+    }
+
     let now = Instant::now();
-    part1(&tree);
+    part1(&graph);
     log::info!("Part 1: {}ms", now.elapsed().as_millis());
-    part2(&tree);
+    part2(&graph);
     log::info!("Part 1 + 2: {}ms", now.elapsed().as_millis());
 }
 
@@ -120,7 +110,7 @@ mod tests {
     use super::*;
 
     #[test]
-    fn build_tree_from_rules_test() {
+    fn build_graph_from_rules_test() {
         let lines = vec![
             "light red bags contain 1 bright white bag, 2 muted yellow bags.",
             "dark orange bags contain 3 bright white bags, 4 muted yellow bags.",
@@ -132,13 +122,29 @@ mod tests {
             "faded blue bags contain no other bags.",
             "dotted black bags contain no other bags."
         ].iter().map(|x| x.to_string()).collect();
-        let tree = build_tree_from_rules(&lines);
+        let graph = build_graph_from_rules(&lines);
+
+        assert_eq!(2, graph.edges(graph.id(&"light red".to_string()).unwrap()).len());
+        assert_eq!(2, graph.edges(graph.id(&"dark orange".to_string()).unwrap()).len());
+        assert_eq!(1, graph.edges(graph.id(&"bright white".to_string()).unwrap()).len());
+        assert_eq!(2, graph.edges(graph.id(&"muted yellow".to_string()).unwrap()).len());
+        assert_eq!(2, graph.edges(graph.id(&"shiny gold".to_string()).unwrap()).len());
+        assert_eq!(9, graph.len());
+        assert_eq!(0, graph.edges(graph.id(&"faded blue".to_string()).unwrap()).len());
+    }
 
-        assert_eq!(2, tree["light red"].len());
-        assert_eq!(2, tree["dark orange"].len());
-        assert_eq!(1, tree["bright white"].len());
-        assert_eq!(2, tree["muted yellow"].len());
-        assert_eq!(2, tree["shiny gold"].len());
+    #[test]
+    fn rule_parser_test() {
+        assert_eq!(
+            Ok(("muted yellow".to_string(), vec![(2, "shiny gold".to_string()), (9, "faded blue".to_string())])),
+            parse::parse_all(rule_parser(), "muted yellow bags contain 2 shiny gold bags, 9 faded blue bags."));
+        assert_eq!(
+            Ok(("bright white".to_string(), vec![(1, "shiny gold".to_string())])),
+            parse::parse_all(rule_parser(), "bright white bags contain 1 shiny gold bag."));
+        assert_eq!(
+            Ok(("faded blue".to_string(), Vec::new())),
+            parse::parse_all(rule_parser(), "faded blue bags contain no other bags."));
+        assert!(parse::parse_all(rule_parser(), "faded blue bags contain 2 shiny gold bags").is_err());
     }
 
     #[test]
@@ -154,9 +160,9 @@ mod tests {
             "faded blue bags contain no other bags.",
             "dotted black bags contain no other bags."
         ].iter().map(|x| x.to_string()).collect();
-        let tree = build_tree_from_rules(&lines);
+        let graph = build_graph_from_rules(&lines);
 
-        assert_eq!(4, part1(&tree));
+        assert_eq!(4, part1(&graph));
     }
 
     #[test]
@@ -170,7 +176,7 @@ mod tests {
             "dark blue bags contain 2 dark violet bags.",
             "dark violet bags contain no other bags.",
         ].iter().map(|x| x.to_string()).collect();
-        let tree = build_tree_from_rules(&lines);
-        assert_eq!(126, part2(&tree));
+        let graph = build_graph_from_rules(&lines);
+        assert_eq!(126, part2(&graph));
     }
 }
\ No newline at end of file
@@ -1,40 +1,56 @@
-use std::collections::HashSet;
-use std::path::Path as Path;
 use std::time::Instant;
 
 use crate::common::io;
+use crate::common::cycle;
+use crate::common::parse;
+use crate::common::parse::{literal, map, pair, terminated};
 
-fn run_program_until_loop(instructions: &Vec<String>) -> (i32, i32) {
-    let mut visited: HashSet<i32> = HashSet::new();
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+enum Operation {
+    Acc,
+    Jmp,
+    Nop
+}
+
+type Instruction = (Operation, i32);
+
+// "acc +1" style lines, the operand is always signed
+fn parse_program(lines: &[String]) -> Vec<Instruction> {
+    let operation = parse::alt(
+        map(literal("acc"), |_| Operation::Acc),
+        parse::alt(map(literal("jmp"), |_| Operation::Jmp), map(literal("nop"), |_| Operation::Nop)));
+    let instruction = pair(terminated(operation, literal(" ")), parse::integer::<i32>());
+    parse::parse_lines(instruction, lines).unwrap()
+}
+
+// Run the instruction at ip, returns the next ip and the change to the
+// accumulator. Once the program runs off the end it stays there, that way
+// every run ends in a cycle and terminating is a cycle of length 1
+fn execute(instructions: &[Instruction], ip: i32) -> (i32, i32) {
+    if ip < 0 || ip >= instructions.len() as i32 {
+        return (ip, 0);
+    }
+
+    let (operation, operrand) = instructions[ip as usize];
+    match operation {
+        Operation::Acc => (ip + 1, operrand),
+        Operation::Jmp => (ip + operrand, 0),
+        Operation::Nop => (ip + 1, 0)
+    }
+}
+
+// Only ip decides what runs next, so the first instruction to run twice is the
+// one at step start + length of the ip cycle. Replay that far for the accumulator
+fn run_program_until_loop(instructions: &Vec<Instruction>) -> (i32, i32) {
+    let loop_at = cycle::brent(0, |ip: &i32| execute(instructions, *ip).0);
+    log::debug!("Instruction pointer {:?}", loop_at);
 
     let mut ip = 0;
     let mut acc = 0;
-
-    while !visited.contains(&ip) && ip < instructions.len() as i32{
-        let tokens: Vec<&str> = instructions[ip as usize].split(' ').collect();
-        let instruction = tokens[0].trim();
-        visited.insert(ip);
-
-        match instruction {
-            "acc" => { 
-                let operrand: i32 = tokens[1].trim().parse().unwrap();
-                acc += operrand;
-                ip += 1;
-                continue;
-            },
-            "jmp" => { 
-                let operrand: i32 = tokens[1].trim().parse().unwrap();
-                ip += operrand;
-                continue;
-            },
-            "nop" => { 
-                ip += 1;
-                continue;
-            },
-            _     => {
-                panic!("crash and burn");
-            }
-        }
+    for _ in 0..loop_at.start + loop_at.length {
+        let (next, change) = execute(instructions, ip);
+        ip = next;
+        acc += change;
     }
     return (acc, ip);
 }
@@ -43,7 +59,7 @@ fn run_program_until_loop(instructions: &Vec<String>) -> (i32, i32) {
 // instructions result in a accumulator increase (single global register)
 // instructions are executed serially unless jump encountered
 // if a line is executed twice stop and return ip and accumulator there
-fn part1(instructions: &Vec<String>) -> i32 {
+fn part1(instructions: &Vec<Instruction>) -> i32 {
     log::info!("Running Part 1");
     let result = run_program_until_loop(&instructions);
     let acc = result.0;
@@ -54,20 +70,20 @@ fn part1(instructions: &Vec<String>) -> i32 {
 // Given a buggy program, we need to find line to replace jmp with nop or opposite
 // the outcome should be that the program executes to completion, try replacing these
 // one by one until you find one that results in program terminattion
-fn part2(instructions: &Vec<String>) -> i32 {
+fn part2(instructions: &[Instruction]) -> i32 {
     log::info!("Running Part 2");
     
     for i in 0..instructions.len() {
         let mut clone = instructions.to_vec();
-        if instructions[i].contains("jmp") {
-            clone[i] = clone[i].replace("jmp", "nop");
-        } else if instructions[i].contains("nop") {
-            clone[i] = clone[i].replace("nop", "jmp");
+        match instructions[i].0 {
+            Operation::Jmp => clone[i].0 = Operation::Nop,
+            Operation::Nop => clone[i].0 = Operation::Jmp,
+            Operation::Acc => {}
         }
         let result = run_program_until_loop(&clone);
         if  result.1 as usize == instructions.len() {
             log::info!("Found buggy line to be {} before loop", i + 1);
-            log::info!("Instruction: {}", instructions[i]);
+            log::info!("Instruction: {:?} {:+}", instructions[i].0, instructions[i].1);
             log::info!("Accumulator: {}", result.0);
             return (i + 1) as i32;
         }
@@ -76,12 +92,12 @@ fn part2(instructions: &Vec<String>) -> i32 {
     return -1;
 }
 
-pub fn run(filename: impl AsRef<Path>) {
-    let lines = io::lines_from_file(filename);
+pub fn run(source: &io::InputSource) {
+    let program = parse_program(&source.lines());
     let now = Instant::now();
-    part1(&lines);
+    part1(&program);
     log::info!("Part 1: {}ms", now.elapsed().as_millis());
-    part2(&lines);
+    part2(&program);
     log::info!("Part 1 + 2: {}ms", now.elapsed().as_millis());
 }
 
@@ -89,6 +105,12 @@ pub fn run(filename: impl AsRef<Path>) {
 mod tests {
     use super::*;
 
+    #[test]
+    fn parse_program_test() {
+        let lines: Vec<String> = ["nop +0", "acc -99", "jmp +4"].iter().map(|x| x.to_string()).collect();
+        assert_eq!(vec![(Operation::Nop, 0), (Operation::Acc, -99), (Operation::Jmp, 4)], parse_program(&lines));
+    }
+
     #[test]
     fn part1_test() {
         let instructions: Vec<String> = vec![
@@ -102,7 +124,7 @@ mod tests {
             "jmp -4",
             "acc +6",
         ].iter().map(|x| x.to_string()).collect();
-        assert_eq!(5, part1(&instructions));
+        assert_eq!(5, part1(&parse_program(&instructions)));
     }
 
     #[test]
@@ -118,6 +140,6 @@ mod tests {
             "jmp -4",
             "acc +6",
         ].iter().map(|x| x.to_string()).collect();
-        assert_eq!(8, part2(&instructions));
+        assert_eq!(8, part2(&parse_program(&instructions)));
     }
 }
\ No newline at end of file
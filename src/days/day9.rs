@@ -1,95 +1,103 @@
-use std::path::Path as Path;
 use std::time::Instant;
-use std::iter::FromIterator;
+use std::collections::HashMap;
+use std::collections::VecDeque;
 
 use crate::common::convertor;
-use crate::common::search;
+use crate::common::subarray;
 use crate::common::io;
 
-fn is_valid_next_number(numbers: &Vec<i64>, next: i64) -> bool {
-    let mut sorted: Vec<i64> = numbers.clone();
-    sorted.sort();
+// The last preamble numbers along with a count of every sum of two of them.
+// Sliding along swaps one number for the next, which only touches the sums
+// involving those two, so checking the next number is a single lookup
+struct XmasWindow {
+    numbers: VecDeque<i64>,
+    sums: HashMap<i64, usize>
+}
 
-    for number in numbers {
-        let find = next - number;
-        let index = search::binary_search(&sorted, find);
+impl XmasWindow {
+    fn new() -> XmasWindow {
+        XmasWindow {numbers: VecDeque::new(), sums: HashMap::new()}
+    }
 
-        if index >= 0 {
-            return true;
+    fn push(&mut self, number: i64) {
+        for other in &self.numbers {
+            *self.sums.entry(number + other).or_insert(0) += 1;
         }
+        self.numbers.push_back(number);
     }
 
-    return false;
-}
+    fn pop(&mut self) {
+        let number = self.numbers.pop_front().unwrap();
+        for other in &self.numbers {
+            let sum = number + other;
+            let count = self.sums.get_mut(&sum).unwrap();
+            *count -= 1;
+            if *count == 0 {
+                self.sums.remove(&sum);
+            }
+        }
+    }
 
-fn part1(numbers: &Vec<i64>, preamble: i64) -> i32 {
-    log::info!("Running Part 1");
-    for i in 0..numbers.len() - (preamble as usize) - 1 { 
-        let start = i;
-        let end = i + preamble as usize;
-        let next = numbers[end+1];
-        log::debug!("Running on {} to {} next number is {}", start, end, next);
+    fn len(&self) -> usize {
+        self.numbers.len()
+    }
 
-        let part: Vec<i64> = Vec::from_iter(numbers[start..end+1].iter().cloned());
+    // Valid if two numbers at different positions in the window sum up to next
+    fn is_valid(&self, next: i64) -> bool {
+        self.sums.contains_key(&next)
+    }
+}
+
+// Index of every number after the preamble that isn't the sum of two of the
+// preamble numbers right before it
+fn invalid_indices(numbers: &[i64], preamble: usize) -> Vec<usize> {
+    let mut window = XmasWindow::new();
+    let mut invalid: Vec<usize> = Vec::new();
 
-        if !is_valid_next_number(&part, next) {
-            log::info!("Found number breaks the rule {}", next);
-            return (end+1) as i32;
+    for (i, number) in numbers.iter().enumerate() {
+        if i >= preamble && !window.is_valid(*number) {
+            log::debug!("Number {} at {} breaks the rule", number, i);
+            invalid.push(i);
+        }
+        window.push(*number);
+        if window.len() > preamble {
+            window.pop();
         }
     }
-    
-    return -1;
+    invalid
 }
 
-fn part2(numbers: &Vec<i64>, failure: i32) -> i64 {
-    log::info!("Running Part 2");
-    let length = failure as usize;
-    log::info!("Length is {}", length);
-    let mut cumulative: Vec<i64> = vec![0; length];
-
-    let mut sum: i64 = 0;
-    for i in 0..length {
-        sum += numbers[i as usize];
-        log::debug!("i: {} sum: {}", i, sum);
-        cumulative[i as usize] = sum;
+fn part1(numbers: &[i64], preamble: usize) -> i32 {
+    log::info!("Running Part 1");
+    match invalid_indices(numbers, preamble).first() {
+        Some(index) => {
+            log::info!("Found number breaks the rule {}", numbers[*index]);
+            *index as i32
+        },
+        None => -1
     }
+}
 
-    let sum_to_find = numbers[(failure) as usize];
+// Find a contiguous run of at least two numbers before the invalid one that
+// sums up to it, the weakness is the smallest plus the largest in that run
+fn part2(numbers: &[i64], failure: i32) -> i64 {
+    log::info!("Running Part 2");
+    let sum_to_find = numbers[failure as usize];
     log::debug!("Looking for {}", sum_to_find);
 
-    for i in 0..cumulative.len() {
-        let find = sum_to_find + cumulative[i];
-        
-        if find < 0 {
-            continue;
-        }
-
-        log::debug!("Trying to find {} and {}", find, cumulative[i]);
-
-        let j = search::binary_search(&cumulative, find);
-        if j!= -1 && i != j as usize {            
-            let mut min = 10000000000;
-            let mut max = -1;
-
-            for index in i+1..(j+1) as usize {
-                if min > numbers[index] {
-                    min = numbers[index];
-                }
-
-                if max < numbers[index] {
-                    max = numbers[index];
-                }
-            }
-            log::info!("Encryption weakness in your XMAS-encrypted list of numbers {}", min + max);
-            return min + max;
-        }
+    match subarray::find_sum(&numbers[..failure as usize], sum_to_find, 2) {
+        Some(range) => {
+            log::debug!("{} numbers from {} sum to {}", range.len(), range.start, range.sum());
+            let weakness = range.min().unwrap() + range.max().unwrap();
+            log::info!("Encryption weakness in your XMAS-encrypted list of numbers {}", weakness);
+            weakness
+        },
+        None => -1
     }
-
-    return -1;
 }
 
-pub fn run(filename: impl AsRef<Path>) {
-    let numbers = convertor::vector_str_to_int64(io::lines_from_file(filename));
+pub fn run(source: &io::InputSource) {
+    let numbers: Vec<i64> = convertor::parse_lines(&source.lines()).unwrap();
     let now = Instant::now();
     part1(&numbers, 25);
     log::info!("Part 1: {}ms", now.elapsed().as_millis());
@@ -102,11 +110,45 @@ mod tests {
     use super::*;
 
     #[test]
-    fn is_valid_next_number_test() {
-        let numbers = vec![35, 20, 15, 25, 47];
-        let not = vec![95, 102, 117, 150, 182];
-        assert_eq!(true, is_valid_next_number(&numbers, 40));
-        assert_eq!(false, is_valid_next_number(&not, 40));
+    fn invalid_indices_test() {
+        // Preamble of 1 to 25 in a random order, then the candidates one at a time
+        let mut preamble: Vec<i64> = (1..26).collect();
+        preamble.swap(0, 19);
+        preamble.swap(3, 11);
+        for (next, valid) in [(26, true), (49, true), (100, false), (50, false)] {
+            let mut numbers = preamble.clone();
+            numbers.push(next);
+            assert_eq!(!valid, invalid_indices(&numbers, 25) == vec![25]);
+        }
+
+        // With 45 as the 26th number the window has moved past 20
+        let mut numbers: Vec<i64> = vec![20];
+        numbers.extend((1..20).chain(21..26));
+        numbers.push(45);
+        for (next, valid) in [(26, true), (65, false), (64, true), (66, true)] {
+            let mut candidate = numbers.clone();
+            candidate.push(next);
+            assert_eq!(!valid, invalid_indices(&candidate, 25) == vec![26]);
+        }
+    }
+
+    #[test]
+    fn invalid_indices_preamble_lengths_test() {
+        let numbers: Vec<i64> = vec![35, 20, 15, 25, 47, 40, 62, 55, 65, 95, 102, 117, 150, 182, 127, 219, 299, 277, 309, 576];
+        assert_eq!(vec![14], invalid_indices(&numbers, 5));
+        assert_eq!(vec![4, 7, 8, 10, 11, 12, 13, 14, 15, 16, 17, 18], invalid_indices(&numbers, 4));
+        assert_eq!(vec![3, 4, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16, 17, 18], invalid_indices(&numbers, 3));
+        assert_eq!((2..20).collect::<Vec<usize>>(), invalid_indices(&numbers, 2));
+        assert_eq!(Vec::<usize>::new(), invalid_indices(&numbers, 30));
+        assert_eq!(Vec::<usize>::new(), invalid_indices(&[], 5));
+    }
+
+    #[test]
+    fn invalid_indices_short_preamble_test() {
+        // Fewer than two numbers to pick from, nothing can be valid
+        assert_eq!(vec![0, 1, 2], invalid_indices(&[1, 2, 3], 0));
+        assert_eq!(vec![1, 2], invalid_indices(&[1, 2, 3], 1));
+        assert_eq!(Vec::<usize>::new(), invalid_indices(&[], 0));
     }
 
     #[test]
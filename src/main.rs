@@ -1,30 +1,106 @@
 use std::env;
+use std::path::{Path, PathBuf};
 
 mod days;
 mod common;
+use common::io::InputSource;
 extern crate log;
 
+// An --input override wins, then an embedded copy if the binary was built with
+// the embed-inputs feature, then the input file next to the working directory.
+// Fails if the chosen file doesn't exist
+fn resolve_input(day: &str, input_override: Option<String>) -> Result<InputSource, String> {
+    #[cfg(feature = "embed-inputs")]
+    {
+        if input_override.is_none() {
+            if let Some(contents) = common::inputs::embedded(day) {
+                return Ok(InputSource::Embedded(contents));
+            }
+        }
+    }
+
+    let path = match input_override {
+        Some(path) => PathBuf::from(path),
+        None => Path::new("./inputs").join(day),
+    };
+    if !path.is_file() {
+        return Err(format!("No input for {} at {}", day, path.display()));
+    }
+    Ok(InputSource::File(path))
+}
+
+// Days to run, each optionally followed by `--input <path>` for that day
+fn parse_args(args: Vec<String>) -> Vec<(String, Option<String>)> {
+    let mut runs: Vec<(String, Option<String>)> = Vec::new();
+    let mut iter = args.into_iter();
+
+    while let Some(arg) = iter.next() {
+        if arg == "--input" {
+            let path = iter.next().expect("--input needs a path");
+            match runs.last_mut() {
+                Some(run) => run.1 = Some(path),
+                None => panic!("--input must follow the day it applies to"),
+            }
+        } else {
+            runs.push((arg, None));
+        }
+    }
+
+    runs
+}
+
 fn main() {
     env_logger::init();
-    for day in env::args().skip(1) {
+    for (day, input_override) in parse_args(env::args().skip(1).collect()) {
         log::info!("Running -> {}", day);
+        let input = match resolve_input(&day, input_override) {
+            Ok(input) => input,
+            Err(error) => {
+                log::error!("{}", error);
+                continue;
+            }
+        };
+        log::debug!("Reading input from {}", input);
         match day.as_ref() {
-            "day1" => days::day1::run("./inputs/day1"),
-            "day2" => days::day2::run("./inputs/day2"),
-            "day3" => days::day3::run("./inputs/day3"),
-            "day4" => days::day4::run("./inputs/day4"),
-            "day5" => days::day5::run("./inputs/day5"),
-            "day6" => days::day6::run("./inputs/day6"),
-            "day7" => days::day7::run("./inputs/day7"),
-            "day8" => days::day8::run("./inputs/day8"),
-            "day9" => days::day9::run("./inputs/day9"),
-            "day10" => days::day10::run("./inputs/day10"),
-            "day11" => days::day11::run("./inputs/day11"),
-            "day12" => days::day12::run("./inputs/day12"),
-            "day13" => days::day13::run("./inputs/day13"),
-            "day14" => days::day14::run("./inputs/day14"),
-            "day15" => days::day15::run("./inputs/day15"),
+            "day1" => days::day1::run(&input),
+            "day2" => days::day2::run(&input),
+            "day3" => days::day3::run(&input),
+            "day4" => days::day4::run(&input),
+            "day5" => days::day5::run(&input),
+            "day6" => days::day6::run(&input),
+            "day7" => days::day7::run(&input),
+            "day8" => days::day8::run(&input),
+            "day9" => days::day9::run(&input),
+            "day10" => days::day10::run(&input),
+            "day11" => days::day11::run(&input),
+            "day12" => days::day12::run(&input),
+            "day13" => days::day13::run(&input),
+            "day14" => days::day14::run(&input),
+            "day15" => days::day15::run(&input),
+            "day21" => days::day21::run(&input),
+            "day22" => days::day22::run(&input),
+            "day23" => days::day23::run(&input),
+            "day24" => days::day24::run(&input),
+            "day25" => days::day25::run(&input),
             _ => println!("Day not built so far!"),
         }
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_args_test() {
+        let args: Vec<String> = ["day1", "day4", "--input", "my_day4", "day6"].iter().map(|x| x.to_string()).collect();
+        let runs = parse_args(args);
+        assert_eq!(3, runs.len());
+        assert_eq!(("day1".to_string(), None), runs[0]);
+        assert_eq!(("day4".to_string(), Some("my_day4".to_string())), runs[1]);
+        assert_eq!(("day6".to_string(), None), runs[2]);
+        assert_eq!(Ok(InputSource::File(PathBuf::from("src/inputs/day6"))), resolve_input("day6", Some("src/inputs/day6".to_string())));
+        assert_eq!(Err("No input for day99 at ./inputs/day99".to_string()), resolve_input("day99", None));
+        assert!(resolve_input("day6", Some("my_day6".to_string())).is_err());
+    }
+}